@@ -0,0 +1,46 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use project_type_checker::api::{analyze_repo, count_sloc, load_file_mappings, TreeNode};
+use project_type_checker::DetectorRegistry;
+use std::collections::HashMap;
+
+const RUST_SOURCE: &str = "// Entry point\nuse std::io;\n\n/* block\n   comment */\nfn main() {\n    println!(\"hi\");\n}\n";
+
+// A synthetic repository: a manifest plus many small source files
+fn synthetic_repo() -> (Vec<TreeNode>, HashMap<String, Vec<u8>>) {
+    let mut files = HashMap::new();
+    files.insert("Cargo.toml".to_string(), b"[package]\nname = \"bench\"\n".to_vec());
+    for i in 0..500 {
+        files.insert(format!("src/module_{}.rs", i), RUST_SOURCE.as_bytes().to_vec());
+    }
+
+    let tree = files
+        .keys()
+        .map(|path| TreeNode {
+            path: path.clone(),
+            mode: "100644".to_string(),
+            r#type: "blob".to_string(),
+            sha: String::new(),
+            size: None,
+            url: None,
+        })
+        .collect();
+    (tree, files)
+}
+
+fn bench_analysis(c: &mut Criterion) {
+    let mappings = load_file_mappings().expect("extensions.json must be loadable from the crate root");
+    let registry = DetectorRegistry::with_defaults();
+    let (tree, files) = synthetic_repo();
+    let runtime = tokio::runtime::Runtime::new().expect("failed to start runtime");
+
+    c.bench_function("count_sloc", |b| {
+        b.iter(|| black_box(count_sloc("Rust", black_box(RUST_SOURCE))))
+    });
+
+    c.bench_function("analyze_repo", |b| {
+        b.iter(|| runtime.block_on(analyze_repo(&tree, &files, &mappings, &registry)))
+    });
+}
+
+criterion_group!(benches, bench_analysis);
+criterion_main!(benches);
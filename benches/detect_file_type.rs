@@ -0,0 +1,39 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use project_type_checker::api::load_file_mappings;
+
+// A mix of common, compound, exact-name and unknown paths
+const PATHS: &[&str] = &[
+    "src/main.rs",
+    "src/components/App.tsx",
+    "types/index.d.ts",
+    "web/static/app.min.js",
+    "Dockerfile",
+    "package.json",
+    ".github/workflows/ci.yml",
+    "docs/guide.md",
+    "assets/logo.png",
+    "vendor/blob.unknownext",
+];
+
+fn bench_detect_file_type(c: &mut Criterion) {
+    let mappings = load_file_mappings().expect("extensions.json must be loadable from the crate root");
+
+    c.bench_function("classify_linear", |b| {
+        b.iter(|| {
+            for path in PATHS {
+                black_box(mappings.classify_linear(black_box(path)));
+            }
+        })
+    });
+
+    c.bench_function("classify_indexed", |b| {
+        b.iter(|| {
+            for path in PATHS {
+                black_box(mappings.classify(black_box(path)));
+            }
+        })
+    });
+}
+
+criterion_group!(benches, bench_detect_file_type);
+criterion_main!(benches);
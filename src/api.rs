@@ -1,16 +1,34 @@
-use reqwest::header::USER_AGENT;
-use serde::Deserialize;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use clap::ValueEnum;
+use globset::{Glob, GlobBuilder, GlobSet, GlobSetBuilder};
+use indexmap::IndexMap;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::error::Error;
-use std::fs::File;
 use std::env;
 use std::path::Path;
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, instrument, warn};
+
+use crate::cli::{FetchStrategy, Options, OutputFormat, TreeFormat};
+use crate::detectors::{indicator_score, Confidence, DetectorRegistry, Finding, FindingKind};
+use crate::client::{ClientConfig, GitHubClient};
+use crate::error::ApiError;
+use crate::filter::{strip_path_prefix, strip_tree_prefix, PathFilter};
+use crate::forge::{BitbucketForge, Forge, GitLabForge, Host, LocalForge};
+use crate::project_type::ProjectType;
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct GitTree {
     pub sha: String,
     pub url: String,
     pub tree: Vec<TreeNode>,
+    /// Set when GitHub cut a recursive listing short (over ~100k entries)
+    #[serde(default)]
+    pub truncated: bool,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -24,383 +42,3010 @@ pub struct TreeNode {
     pub url: Option<String>,
 }
 
-#[derive(Debug)]
-struct FileStats {
-    files: usize,
+impl TreeNode {
+    /// Git mode of a symbolic link; its blob is just the target path.
+    pub const SYMLINK_MODE: &'static str = "120000";
+    pub const EXECUTABLE_MODE: &'static str = "100755";
+
+    pub fn is_symlink(&self) -> bool {
+        self.mode == Self::SYMLINK_MODE
+    }
+
+    pub fn is_executable(&self) -> bool {
+        self.r#type == "blob" && self.mode == Self::EXECUTABLE_MODE
+    }
+
+    /// A blob whose content is worth fetching, i.e. not a symlink.
+    pub fn is_file(&self) -> bool {
+        self.r#type == "blob" && !self.is_symlink()
+    }
+}
+
+/// Per-file-type tally kept while analyzing.
+#[derive(Debug, Clone, Default)]
+pub struct FileStats {
+    pub files: usize,
+    /// Largest file of this type, as a representative to open first
+    pub example: Option<String>,
+    pub example_size: usize,
 }
 
 impl FileStats {
-    fn new() -> Self {
-        Self { files: 0 }
+    pub fn new() -> Self {
+        Self { files: 0, example: None, example_size: 0 }
+    }
+
+    // Folds another type's tally in, keeping the larger example
+    fn merge(&mut self, other: &FileStats) {
+        self.files += other.files;
+        if let Some(example) = &other.example {
+            self.record_example(example, other.example_size);
+        }
+    }
+
+    fn record(&mut self, path: &str, size: usize) {
+        self.files += 1;
+        self.record_example(path, size);
+    }
+
+    fn record_example(&mut self, path: &str, size: usize) {
+        // Ties go to the smaller path so the pick doesn't depend on map order
+        let larger = size > self.example_size
+            || (size == self.example_size && self.example.as_deref().is_none_or(|e| path < e));
+        if larger {
+            self.example = Some(path.to_string());
+            self.example_size = size;
+        }
     }
 }
 
-#[derive(Deserialize)]
-struct FileTypes {
-    programming_languages: HashMap<String, Vec<String>>,
-    web_files: HashMap<String, Vec<String>>,
-    config_files: HashMap<String, Vec<String>>,
-    documentation: HashMap<String, Vec<String>>,
-    images: HashMap<String, Vec<String>>,
-    video: HashMap<String, Vec<String>>,
-    audio: HashMap<String, Vec<String>>,
-    archives: HashMap<String, Vec<String>>,
-    fonts: HashMap<String, Vec<String>>,
-    other: HashMap<String, Vec<String>>,
+/// Wall-clock time spent in each phase of a run, printed with `--timings`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Timings {
+    pub tree: Duration,
+    pub content: Duration,
+    pub analysis: Duration,
+    /// Files whose contents were fetched
+    pub files: usize,
 }
 
-#[derive(Deserialize)]
-pub struct FileMappings {
-    file_types: FileTypes,
+fn format_duration(duration: Duration) -> String {
+    if duration < Duration::from_secs(1) {
+        format!("{}ms", duration.as_millis())
+    } else {
+        format!("{:.1}s", duration.as_secs_f64())
+    }
 }
 
-pub fn load_file_mappings() -> Result<FileMappings, Box<dyn std::error::Error>> {
-    // Dynamically construct the path to 'src/extensions.json'
-    let _current_dir = env::current_dir()?;
-    let path = Path::new("./extensions.json");
-    if !path.exists() {
-        println!("File does not exist at path: {:?}", path.display());
-    }
-
-    // Check if the file exists
-    if !path.exists() {
-        return Err(format!("File not found: {}", path.display()).into());
-    }
-
-    // Try opening the file using the dynamically constructed path
-    let file = File::open(&path)
-        .map_err(|e| format!("Failed to open file '{}': {}", path.display(), e))?;
-
-    let mappings: FileMappings = serde_json::from_reader(file)
-        .map_err(|e| format!("Failed to parse JSON from '{}': {}", path.display(), e))?;
-
-    Ok(mappings)
-}
-
-async fn detect_file_type(path: &str, mappings: &FileMappings) -> String {
-    let all_types = vec![
-        &mappings.file_types.programming_languages,
-        &mappings.file_types.web_files,
-        &mappings.file_types.config_files,
-        &mappings.file_types.documentation,
-        &mappings.file_types.images,
-        &mappings.file_types.video,
-        &mappings.file_types.audio,
-        &mappings.file_types.archives,
-        &mappings.file_types.fonts,
-        &mappings.file_types.other,
-    ];
-
-    for types_map in all_types {
-        for (file_type, patterns) in types_map {
-            for pattern in patterns {
-                if path.ends_with(pattern.trim_start_matches('*')) {
-                    println!("Matched file type: {} for file: {}", file_type, path);
-                    return file_type.clone();
-                }
-            }
-        }
+impl std::fmt::Display for Timings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "tree: {}, content: {} ({} files), analysis: {}",
+            format_duration(self.tree),
+            format_duration(self.content),
+            self.files,
+            format_duration(self.analysis)
+        )
     }
+}
 
-    println!("Unknown file type for file: {}", path);
-    "Unknown".to_string()
+/// Path depth across the tree's files, counted in `/`-separated segments.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DepthStats {
+    pub max: usize,
+    pub average: f64,
 }
 
-fn detect_framework(path: &str, content: &str) -> String {
-    let mut frameworks = HashMap::new();
-    frameworks.insert("next.config.js", "Next.js");
-    frameworks.insert("next.config.mjs", "Next.js");
-    frameworks.insert("vue.config", "Vue.js");
-    frameworks.insert("angular.json", "Angular");
+impl DepthStats {
+    pub fn from_tree(tree: &[TreeNode]) -> Self {
+        let depths: Vec<usize> = tree
+            .iter()
+            .filter(|node| node.is_file())
+            .map(|node| node.path.split('/').count())
+            .collect();
 
-    // Detect based on path contents
-    for (key, framework) in &frameworks {
-        if path.contains(key) {
-            return framework.to_string();
+        if depths.is_empty() {
+            return Self::default();
         }
-    }
 
-    // Additional detection for frameworks in package.json
-    let mut package_json_frameworks = HashMap::new();
-    package_json_frameworks.insert("react", "React");
-    package_json_frameworks.insert("vue", "Vue.js");
-    package_json_frameworks.insert("angular", "Angular");
-
-    if path.contains("package.json") {
-        for (key, framework) in &package_json_frameworks {
-            if content.contains(key) {
-                return framework.to_string();
-            }
+        Self {
+            max: depths.iter().copied().max().unwrap_or(0),
+            average: depths.iter().sum::<usize>() as f64 / depths.len() as f64,
         }
     }
+}
+
+/// Conventional top-level directory names and the role they usually play.
+const DIRECTORY_ROLES: &[(&str, &[&str])] = &[
+    ("source", &["src", "lib", "app", "pkg", "cmd", "internal", "crates", "packages", "source"]),
+    ("tests", &["test", "tests", "spec", "specs", "__tests__", "testing"]),
+    ("docs", &["docs", "doc", "documentation"]),
+    ("tooling", &["scripts", "script", "tools", "bin", "hack"]),
+    ("examples", &["examples", "example", "samples", "demo", "demos"]),
+    ("benchmarks", &["benches", "bench", "benchmarks"]),
+    ("assets", &["assets", "static", "public", "resources"]),
+    ("vendored", &["vendor", "third_party", "thirdparty", "external"]),
+    ("CI", &[".github", ".circleci", ".gitlab", ".buildkite"]),
+];
 
-    "None".to_string()
+/// A top-level directory and the role its name suggests, if it is a conventional one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopLevelDir {
+    pub name: String,
+    pub role: Option<&'static str>,
 }
 
-fn detect_project_type_and_framework(path: &str, content: &str) -> (Option<String>, Option<String>) {
-    let mut project_types = HashMap::new();
+// Taken from file paths rather than directory entries, so change lists (--since,
+// --pr) get a layout too and directories emptied by --exclude are left out
+pub fn top_level_layout(tree: &[TreeNode]) -> Vec<TopLevelDir> {
+    let mut names: Vec<&str> = tree
+        .iter()
+        .filter(|node| node.is_file())
+        .filter_map(|node| node.path.split_once('/').map(|(dir, _)| dir))
+        .collect();
+    names.sort_unstable();
+    names.dedup();
 
-    // Define indicators for different types of projects
-    project_types.insert("pom.xml", "Java Backend");
-    project_types.insert("config.ru", "Ruby Backend (Rails)");
-    project_types.insert("main.go", "Go Backend");
-    project_types.insert("index.php", "PHP Backend");
-    project_types.insert("build.gradle", "Kotlin Backend");
-    project_types.insert("build.sbt", "Scala Backend");
+    names
+        .into_iter()
+        .map(|name| TopLevelDir {
+            name: name.to_string(),
+            role: DIRECTORY_ROLES
+                .iter()
+                .find(|(_, dirs)| dirs.contains(&name.to_ascii_lowercase().as_str()))
+                .map(|(role, _)| *role),
+        })
+        .collect()
+}
 
-    // Define indicators for mobile and desktop apps
-    project_types.insert("AndroidManifest.xml", "Mobile App");
-    project_types.insert("Info.plist", "Mobile App");
-    project_types.insert("MainActivity.java", "Mobile App");
-    project_types.insert("AppDelegate.swift", "Mobile App");
-    project_types.insert("electron", "Desktop App");
-    project_types.insert(".desktop", "Desktop App");
-    project_types.insert("MainWindow.xaml", "Desktop App");
+/// File type name to the glob-like patterns that identify it, in declaration order.
+pub type TypeMap = IndexMap<String, Vec<String>>;
 
-    // Define indicators for CLI tools
-    project_types.insert("Cargo.toml", "Rust CLI Tool");
-    project_types.insert("setup.py", "Python CLI Tool");
-    project_types.insert("Makefile", "CLI Tool");
-    project_types.insert("Program.cs", "C# CLI Tool");
-    project_types.insert("pom.xml", "Java CLI Tool");
-    project_types.insert("build.gradle", "Gradle (Java/Kotlin) CLI Tool");
-    project_types.insert("Go.mod", "Go CLI Tool");
-    project_types.insert("Rakefile", "Ruby CLI Tool");
+/// Display label to the file types it gathers, from the optional `groups` section.
+pub type TypeGroups = IndexMap<String, Vec<String>>;
 
-    let framework = detect_framework(path, content);
+/// The category maps from `extensions.json`.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct FileTypes {
+    pub programming_languages: TypeMap,
+    pub web_files: TypeMap,
+    pub config_files: TypeMap,
+    pub documentation: TypeMap,
+    pub images: TypeMap,
+    pub video: TypeMap,
+    pub audio: TypeMap,
+    pub archives: TypeMap,
+    pub fonts: TypeMap,
+    pub other: TypeMap,
+}
 
-    // Check if it's a website
-    if path.ends_with(".html") || path.ends_with(".css") {
-        if framework != "None" {
-            return (Some("Website".to_string()), Some(format!("Website using {}", framework)));
-        } else {
-            return (Some("Website".to_string()), Some("Static website".to_string()));
-        }
+impl FileTypes {
+    // Category order doubles as match precedence
+    pub fn categories(&self) -> [(&'static str, &TypeMap); 10] {
+        [
+            ("programming_languages", &self.programming_languages),
+            ("web_files", &self.web_files),
+            ("config_files", &self.config_files),
+            ("documentation", &self.documentation),
+            ("images", &self.images),
+            ("video", &self.video),
+            ("audio", &self.audio),
+            ("archives", &self.archives),
+            ("fonts", &self.fonts),
+            ("other", &self.other),
+        ]
     }
 
-    // Check for other project types
-    for (key, project_type) in &project_types {
-        if path.contains(key) || content.contains(key) {
-            return (Some(project_type.to_string()), None);
+    /// Reads each category on its own, so a malformed or missing one is skipped with
+    /// a warning while the others still load.
+    fn from_json_lenient(file_types: &serde_json::Map<String, serde_json::Value>, source: &str) -> Self {
+        let mut parsed = FileTypes::default();
+        let categories: [(&str, &mut TypeMap); 10] = [
+            ("programming_languages", &mut parsed.programming_languages),
+            ("web_files", &mut parsed.web_files),
+            ("config_files", &mut parsed.config_files),
+            ("documentation", &mut parsed.documentation),
+            ("images", &mut parsed.images),
+            ("video", &mut parsed.video),
+            ("audio", &mut parsed.audio),
+            ("archives", &mut parsed.archives),
+            ("fonts", &mut parsed.fonts),
+            ("other", &mut parsed.other),
+        ];
+
+        for (category, map) in categories {
+            match file_types.get(category).map(TypeMap::deserialize) {
+                Some(Ok(types)) => *map = types,
+                Some(Err(e)) => warn!("Skipping category '{}' in {}: {}", category, source, e),
+                None => warn!("Category '{}' is missing from {}", category, source),
+            }
         }
+        parsed
     }
+}
 
-    // Default to None if no project type is matched
-    (None, None)
+/// Inverted view of the mappings so classifying a path is a hash lookup instead of
+/// a scan over every pattern. Each pattern gets a rank in declaration order; when
+/// several patterns match, a file name or glob beats a plain `*.ext`, and otherwise
+/// the lowest rank wins, just like the linear scan.
+#[derive(Default)]
+struct FileTypeIndex {
+    entries: Vec<(String, &'static str)>,
+    by_extension: HashMap<String, usize>,
+    by_name: HashMap<String, usize>,
+    // Compound suffixes (`*.d.ts`) and other globs, matched against the file name
+    name_globs: RankedGlobs,
+    // Patterns with a directory part (`.github/workflows/*.yml`), matched against the path
+    path_globs: RankedGlobs,
 }
 
-async fn analyze_files(
-    files: &HashMap<String, String>,
-    mappings: &FileMappings,
-) -> (HashMap<String, FileStats>, Vec<String>) {
-    let mut file_stats = HashMap::new();
-    let mut project_types_detected = Vec::new();
+/// A glob set plus the rank of the pattern behind each glob in it.
+#[derive(Default)]
+struct RankedGlobs {
+    set: GlobSet,
+    ranks: Vec<usize>,
+}
 
-    for (path, content) in files {
-        let file_type = detect_file_type(path, mappings).await;
-        let (project_type, project_type_with_framework) = detect_project_type_and_framework(path, content);
+impl RankedGlobs {
+    fn build(patterns: Vec<(&str, usize)>) -> Self {
+        let mut builder = GlobSetBuilder::new();
+        let mut ranks = Vec::new();
 
-        // Add the detected project type and framework to the list if not already present
-        if let Some(project_type) = project_type {
-            if !project_types_detected.contains(&project_type) {
-                project_types_detected.push(project_type);
+        for (pattern, rank) in patterns {
+            match file_type_glob(pattern) {
+                Ok(glob) => {
+                    builder.add(glob);
+                    ranks.push(rank);
+                }
+                Err(e) => warn!("Skipping invalid pattern '{}': {}", pattern, e),
             }
         }
 
-        if let Some(project_type_with_framework) = project_type_with_framework {
-            if !project_types_detected.contains(&project_type_with_framework) {
-                project_types_detected.push(project_type_with_framework);
+        match builder.build() {
+            Ok(set) => Self { set, ranks },
+            Err(e) => {
+                warn!("Failed to build file type globs: {}", e);
+                Self::default()
             }
         }
+    }
 
-        // Update the file stats
-        let type_entry = file_stats.entry(file_type).or_insert_with(FileStats::new);
-        type_entry.files += 1;
-    }
-
-    (file_stats, project_types_detected)
-}
-fn detect_combined_project_type(project_types: &[String]) -> String {
-    let project_combinations = vec![
-        (vec!["Website", "Rust Backend"], "Website with Rust Backend"),
-        (vec!["Website", "Python Backend"], "Website with Python Backend"),
-        (vec!["Website", "C# Backend"], "Website with .NET Backend"),
-        (vec!["Website", "Node.js Backend"], "Website with Node.js Backend"),
-        (vec!["Website", "Java Backend"], "Website with Node.js Backend"),
-        (vec!["Website", "Ruby Backend (Rails)"], "Website with Rust Backend"),
-        (vec!["Website", "Go Backend"], "Website with Python Backend"),
-        (vec!["Website", "PHP Backend"], "Website with .NET Backend"),
-        (vec!["Website", "Kotlin Backend"], "Website with Node.js Backend"),
-        (vec!["Website", "Scala Backend"], "Website with Node.js Backend"),
-        // (vec!["Website"], "Website"),
-        (vec!["Mobile App"], "Mobile App"),
-        (vec!["Desktop App"], "Desktop App"),
-        (vec!["CLI Tool"], "CLI Tool"),
-    ];
-
-    project_combinations.iter()
-        .find(|(types, _)| types.iter().all(|t| project_types.contains(&t.to_string())))
-        .map(|(_, description)| description.to_string())
-        .unwrap_or_else(|| "Unknown Project Type".to_string())
-}
-
-fn display_file_stats(file_stats: &HashMap<String, FileStats>, project_types: Vec<String>) {
-    println!("Repository contents:");
-    println!("--------------------------------------------------");
-    
-    for (file_type, stats) in file_stats {
-        println!("File Type: {}", file_type);
-        println!("Files: {}", stats.files);
-        println!("--------------------------------------------------");
+    fn matches<'a>(&'a self, target: &str) -> impl Iterator<Item = &'a usize> {
+        self.set
+            .matches(target)
+            .into_iter()
+            .map(move |i| &self.ranks[i])
     }
-    
-    let combined_project_type = detect_combined_project_type(&project_types);
-    println!("Detected Project Type: {}", combined_project_type);
 }
 
-async fn fetch_files(
-    client: &reqwest::Client,
-    tree: &[TreeNode],
-) -> Result<HashMap<String, String>, Box<dyn Error>> {
-    let mut files = HashMap::new();
+// `*` stays within one path segment, so `.github/workflows/*.yml` skips subdirectories
+fn file_type_glob(pattern: &str) -> Result<Glob, globset::Error> {
+    GlobBuilder::new(pattern).literal_separator(true).build()
+}
 
-    for node in tree {
-        if node.r#type == "blob" {
-            let url = match &node.url {
-                Some(url) => url,
-                None => {
-                    eprintln!("Skipping file {} due to missing URL.", node.path);
-                    continue;
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+impl FileTypeIndex {
+    fn build(file_types: &FileTypes) -> Self {
+        let mut index = Self::default();
+        let mut name_globs = Vec::new();
+        let mut path_globs = Vec::new();
+
+        for (category, types_map) in file_types.categories() {
+            for (file_type, patterns) in types_map {
+                for pattern in patterns {
+                    let rank = index.entries.len();
+                    index.entries.push((file_type.clone(), category));
+
+                    match plain_extension(pattern) {
+                        Some(ext) => {
+                            index.by_extension.entry(ext.to_string()).or_insert(rank);
+                        }
+                        _ if !pattern.contains(['*', '/']) => {
+                            index.by_name.entry(pattern.clone()).or_insert(rank);
+                        }
+                        _ if pattern.contains('/') => path_globs.push((pattern.as_str(), rank)),
+                        _ => name_globs.push((pattern.as_str(), rank)),
+                    }
                 }
-            };
+            }
+        }
+
+        index.name_globs = RankedGlobs::build(name_globs);
+        index.path_globs = RankedGlobs::build(path_globs);
+        index
+    }
 
-            let file_res = client.get(url).header(USER_AGENT, "rust-tool").send().await?;
-
-            if file_res.status().is_success() {
-                let content = file_res.text().await?;
-                files.insert(node.path.clone(), content);
-            } else {
-                eprintln!(
-                    "Failed to fetch file {}: {} - {}",
-                    node.path,
-                    file_res.status(),
-                    file_res.text().await?
-                );
+    fn lookup(&self, path: &str) -> Option<(&str, &'static str)> {
+        let file_name = file_name(path);
+        let by_extension = file_name
+            .rsplit_once('.')
+            .and_then(|(_, ext)| self.by_extension.get(ext));
+
+        self.by_name
+            .get(file_name)
+            .into_iter()
+            .chain(self.name_globs.matches(file_name))
+            .chain(self.path_globs.matches(path))
+            .min()
+            .or(by_extension)
+            .map(|&rank| {
+                let (file_type, category) = &self.entries[rank];
+                (file_type.as_str(), *category)
+            })
+    }
+}
+
+/// The extension of a plain `*.ext` pattern; `None` for names, compound suffixes
+/// (`*.d.ts`) and other globs, which are more specific and take precedence.
+fn plain_extension(pattern: &str) -> Option<&str> {
+    pattern
+        .strip_prefix("*.")
+        .filter(|ext| !ext.contains(['.', '*', '/']))
+}
+
+/// Whether one `extensions.json` pattern matches `path`: a bare name (`Dockerfile`)
+/// must equal the file name, `*.ext` and compound suffixes (`*.d.ts`) match the end
+/// of the file name, and patterns with a directory part match the whole path.
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let target = if pattern.contains('/') { path } else { file_name(path) };
+
+    match pattern.strip_prefix('*') {
+        Some(suffix) if !suffix.contains(['*', '/']) => target.ends_with(suffix),
+        _ if pattern.contains('*') => file_type_glob(pattern)
+            .map(|glob| glob.compile_matcher().is_match(target))
+            .unwrap_or(false),
+        _ => target == pattern,
+    }
+}
+
+#[derive(Deserialize)]
+pub struct FileMappings {
+    file_types: FileTypes,
+    #[serde(default)]
+    groups: TypeGroups,
+    #[serde(skip)]
+    index: FileTypeIndex,
+}
+
+impl FileMappings {
+    fn new(file_types: FileTypes, groups: TypeGroups) -> Self {
+        Self {
+            index: FileTypeIndex::build(&file_types),
+            file_types,
+            groups,
+        }
+    }
+
+    pub fn file_types(&self) -> &FileTypes {
+        &self.file_types
+    }
+
+    /// Display groups for `--group`; detection never looks at them
+    pub fn groups(&self) -> &TypeGroups {
+        &self.groups
+    }
+
+    /// Classifies a path using the prebuilt extension index.
+    pub fn classify(&self, path: &str) -> Option<(&str, &'static str)> {
+        self.index.lookup(path)
+    }
+
+    /// Category of a file type by name, e.g. "programming_languages" for "Python"
+    pub fn category_of(&self, file_type: &str) -> Option<&'static str> {
+        self.file_types
+            .categories()
+            .into_iter()
+            .find(|(_, types_map)| types_map.contains_key(file_type))
+            .map(|(category, _)| category)
+    }
+
+    /// Reference implementation scanning every pattern; kept for benchmarking the index.
+    #[doc(hidden)]
+    pub fn classify_linear(&self, path: &str) -> Option<(&str, &'static str)> {
+        let mut by_extension = None;
+        for (category, types_map) in self.file_types.categories() {
+            for (file_type, patterns) in types_map {
+                for pattern in patterns {
+                    if !pattern_matches(pattern, path) {
+                        continue;
+                    }
+                    if plain_extension(pattern).is_none() {
+                        return Some((file_type.as_str(), category));
+                    }
+                    by_extension.get_or_insert((file_type.as_str(), category));
+                }
             }
         }
+        by_extension
     }
+}
 
-    Ok(files)
+/// Where `load_file_mappings` looks for user mappings, relative to the working directory.
+pub const MAPPINGS_PATH: &str = "./extensions.json";
+
+/// The mappings this binary was built with, used when `extensions.json` in the
+/// working directory is missing or cannot be parsed at all.
+pub const EMBEDDED_MAPPINGS: &str = include_str!("../extensions.json");
+
+// Only a file that is not JSON, or has no `file_types` object, is rejected outright
+pub(crate) fn parse_mappings(json: &str, source: &str) -> Result<FileMappings, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| format!("Failed to parse JSON from '{}': {}", source, e))?;
+    let file_types = match value.get("file_types").and_then(serde_json::Value::as_object) {
+        Some(file_types) => FileTypes::from_json_lenient(file_types, source),
+        None => return Err(format!("'{}' has no \"file_types\" object", source)),
+    };
+
+    let groups = match value.get("groups").map(TypeGroups::deserialize) {
+        Some(Ok(groups)) => groups,
+        Some(Err(e)) => {
+            warn!("Ignoring \"groups\" in {}: {}", source, e);
+            TypeGroups::new()
+        }
+        None => TypeGroups::new(),
+    };
+    Ok(FileMappings::new(file_types, groups))
 }
 
-pub async fn fetch_and_display_tree(github_url: &str) -> Result<(), Box<dyn Error>> {
-    let (owner, repo) = extract_owner_repo(github_url)?;
-    let client = reqwest::Client::new();
+/// Loads `./extensions.json`. Malformed categories are skipped with a warning; when
+/// the file is missing or unusable as a whole, the embedded mappings are used instead.
+pub fn load_file_mappings() -> Result<FileMappings, Box<dyn std::error::Error>> {
+    let path = Path::new(MAPPINGS_PATH);
+    let parsed = match std::fs::read_to_string(path) {
+        Ok(json) => parse_mappings(&json, &path.display().to_string()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            debug!("{} not found; using the built-in mappings", path.display());
+            return embedded_file_mappings();
+        }
+        Err(e) => Err(format!("Failed to open file '{}': {}", path.display(), e)),
+    };
 
-    // Load file mappings
-    let mappings = match load_file_mappings() {
-        Ok(m) => m,
+    match parsed {
+        Ok(mappings) => Ok(mappings),
         Err(e) => {
-            eprintln!("Error loading file mappings: {}", e);
-            return Ok(());
+            warn!("{}; using the built-in mappings", e);
+            embedded_file_mappings()
         }
-    };
+    }
+}
 
-    // Fetch repository info
-    let repo_url = format!("https://api.github.com/repos/{}/{}", owner, repo);
-    let repo_res = client
-        .get(&repo_url)
-        .header(USER_AGENT, "rust-tool")
-        .send().await?;
+pub fn embedded_file_mappings() -> Result<FileMappings, Box<dyn std::error::Error>> {
+    Ok(parse_mappings(EMBEDDED_MAPPINGS, "built-in mappings")?)
+}
 
-    if !repo_res.status().is_success() {
-        eprintln!(
-            "Failed to fetch repository info: {} - {}",
-            repo_res.status(),
-            repo_res.text().await?
-        );
-        return Ok(()); // or Err(e) if you want to propagate the error
+/// A path's detected file type and the `extensions.json` category it belongs to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileType {
+    /// e.g. "Rust" or "Package Managers"; "Unknown" when nothing matched
+    pub name: String,
+    /// e.g. "programming_languages" or "config_files"; "unknown" when nothing matched
+    pub category: &'static str,
+}
+
+/// Classifies `path` by the mappings without logging or other side effects.
+pub fn classify_path(path: &str, mappings: &FileMappings) -> FileType {
+    match mappings.classify(path) {
+        Some((name, category)) => FileType {
+            name: name.to_string(),
+            category,
+        },
+        None => FileType {
+            name: "Unknown".to_string(),
+            category: "unknown",
+        },
     }
+}
 
-    let repo_info: serde_json::Value = repo_res.json().await?;
-    let default_branch = repo_info["default_branch"]
-        .as_str()
-        .unwrap_or("main")
-        .to_string();
+/// Script interpreters named in a `#!` line, and the file type their scripts are.
+const SHEBANG_INTERPRETERS: &[(&str, &str)] = &[
+    ("sh", "Shell"),
+    ("bash", "Shell"),
+    ("zsh", "Shell"),
+    ("dash", "Shell"),
+    ("ksh", "Shell"),
+    ("fish", "Shell"),
+    ("node", "JavaScript"),
+    ("nodejs", "JavaScript"),
+    ("bun", "JavaScript"),
+    ("deno", "TypeScript"),
+    ("ts-node", "TypeScript"),
+    ("tsx", "TypeScript"),
+    ("python", "Python"),
+    ("pypy", "Python"),
+    ("ruby", "Ruby"),
+    ("perl", "Perl"),
+    ("php", "PHP"),
+    ("pwsh", "PowerShell"),
+    ("powershell", "PowerShell"),
+    ("lua", "Lua"),
+    ("Rscript", "R"),
+    ("elixir", "Elixir"),
+    ("tclsh", "Tcl"),
+];
 
-    let tree_url = format!(
-        "https://api.github.com/repos/{}/{}/git/trees/{}?recursive=1",
-        owner, repo, default_branch
-    );
+/// File type of an extensionless script from its first line: the interpreter of a
+/// `#!` line (looking through `/usr/bin/env`, its flags and version suffixes such as
+/// `python3.11`), or a PowerShell `#Requires` statement.
+fn sniff_script_type(content: &str) -> Option<&'static str> {
+    let first_line = content.lines().next()?.trim();
+    if first_line.to_ascii_lowercase().starts_with("#requires") {
+        return Some("PowerShell");
+    }
 
-    println!("Tree URL: {}", tree_url);
+    let mut words = first_line.strip_prefix("#!")?.split_whitespace();
+    let mut interpreter = file_name(words.next()?);
+    if interpreter == "env" {
+        interpreter = words.find(|word| !word.starts_with('-') && !word.contains('='))?;
+    }
+    let interpreter = interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
 
-    // Fetch tree
-    let tree_res = client
-        .get(&tree_url)
-        .header(USER_AGENT, "rust-tool")
-        .send().await?;
+    SHEBANG_INTERPRETERS
+        .iter()
+        .find(|(name, _)| *name == interpreter)
+        .map(|(_, file_type)| *file_type)
+}
 
-    if tree_res.status().is_success() {
-        let tree: GitTree = tree_res.json().await?;
-        crate::display::print_tree(&tree.tree, 0);
+// Returns the detected file type together with the mappings category it came from
+fn detect_file_type(path: &str, mappings: &FileMappings) -> (String, &'static str) {
+    let file_type = classify_path(path, mappings);
+    debug!("Detected file type: {} for file: {}", file_type.name, path);
+    (file_type.name, file_type.category)
+}
 
-        // Fetch file contents
-        let files = fetch_files(&client, &tree.tree).await?;
-        let (file_stats, framework_message) = analyze_files(&files, &mappings).await;
-        display_file_stats(&file_stats, framework_message);
+#[derive(Debug, Clone, Serialize)]
+pub struct FileRecord {
+    pub path: String,
+    #[serde(rename = "type")]
+    pub file_type: String,
+    pub category: String,
+    pub size: usize,
+    pub lines: usize,
+    /// Lines that are neither blank nor comments (see `COMMENT_SYNTAX`)
+    pub sloc: usize,
+    /// Likely produced by a code generator (see `is_generated`)
+    pub generated: bool,
+    /// Minified JavaScript/CSS (see `is_minified`); its `sloc` is zero
+    pub minified: bool,
+    /// Git blob SHA from the tree; only set with `--include-content-hash`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha: Option<String>,
+}
 
-    } else {
-        eprintln!(
-            "Failed to fetch the repo tree: {} - {}",
-            tree_res.status(),
-            tree_res.text().await?
-        );
+impl FileRecord {
+    /// Neither generated nor minified, so it counts towards the language breakdown
+    pub fn is_hand_written(&self) -> bool {
+        !self.generated && !self.minified
     }
+}
 
-    Ok(())
+/// A file in the tree that was not analyzed, and why.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SkipRecord {
+    pub path: String,
+    pub reason: String,
 }
 
-fn extract_owner_repo(github_url: &str) -> Result<(String, String), Box<dyn Error>> {
-    let url_parts: Vec<&str> = github_url.split('/').collect();
-    if url_parts.len() < 5 {
-        return Err(format!("Invalid GitHub URL: {}", github_url).into());
+impl SkipRecord {
+    pub fn new(path: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            reason: reason.into(),
+        }
     }
-    let owner = url_parts[3].to_string();
-    let repo = url_parts[4].to_string();
-    Ok((owner, repo))
 }
 
-pub fn print_tree(tree: &[TreeNode], level: usize) {
-    for node in tree {
-        for _ in 0..level {
-            print!("  ");
+/// Symlinks are never fetched, so they don't count against detection.
+const SYMLINK_SKIP_REASON: &str = "symlink; target not fetched";
+
+/// Whether content-based detection (frameworks, manifests, project type) saw
+/// every file, or worked from an incomplete set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DetectionConfidence {
+    Full,
+    Limited,
+}
+
+/// Fetched contents by path, plus the files that could not be fetched.
+pub type FetchedFiles = (HashMap<String, Vec<u8>>, Vec<SkipRecord>);
+
+#[derive(Debug, Clone)]
+pub struct RepoAnalysis {
+    file_stats: HashMap<String, FileStats>,
+    pub project_types: Vec<ProjectType>,
+    /// Per-file classification, sorted by path
+    pub files: Vec<FileRecord>,
+    /// Everything reported by the detector registry
+    pub findings: Vec<Finding>,
+    /// Highest-confidence overall classification
+    pub project_type: ProjectType,
+    pub confidence: Confidence,
+    /// Nesting of the analyzed tree; zero when there is no tree (e.g. gists)
+    pub depth: DepthStats,
+    /// Set when `--deadline` cut fetching short, so only some files were analyzed
+    pub partial: bool,
+    /// Top-level directories with their guessed roles, sorted by name
+    pub layout: Vec<TopLevelDir>,
+    /// With `--sample`, how many files the sample was drawn from
+    pub sampled_from: Option<usize>,
+    /// Symlinks in the tree; their targets are not fetched or classified
+    pub symlinks: usize,
+    /// Files committed with the executable bit (mode 100755)
+    pub executables: usize,
+    /// Files in the tree that were not analyzed, sorted by path
+    pub skipped: Vec<SkipRecord>,
+    /// Bytes of files in `ASSET_CATEGORIES`, from the tree's blob sizes
+    pub asset_bytes: u64,
+    /// Bytes of all files, from the tree's blob sizes
+    pub total_bytes: u64,
+    /// The mappings' display groups, for `--group`
+    pub type_groups: TypeGroups,
+    /// Per-phase durations; `tree` and `content` are filled in by the caller
+    pub timings: Timings,
+}
+
+impl RepoAnalysis {
+    pub fn file_stats(&self) -> &HashMap<String, FileStats> {
+        &self.file_stats
+    }
+
+    /// Detected file type for every analyzed path, in the order of `files`
+    pub fn file_types(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.files.iter().map(|f| (f.path.as_str(), f.file_type.as_str()))
+    }
+
+    /// Like `sorted_file_stats`, but types listed in one of `type_groups` are added
+    /// up under the group's label (`--group`)
+    pub fn grouped_file_stats(&self) -> Vec<(String, FileStats)> {
+        let mut grouped: HashMap<&str, FileStats> = HashMap::new();
+        for (file_type, stats) in &self.file_stats {
+            let label = self
+                .type_groups
+                .iter()
+                .find(|(_, members)| members.iter().any(|member| member == file_type))
+                .map_or(file_type.as_str(), |(label, _)| label.as_str());
+            grouped.entry(label).or_default().merge(stats);
         }
-        println!("{}", node.path);
-        if node.r#type == "tree" {
-            // If it's a directory, recursively print its contents
-            if let Some(url) = &node.url {
-                if let Ok(sub_tree) = fetch_sub_tree(url) {
-                    print_tree(&sub_tree.tree, level + 1);
-                }
+
+        let mut grouped: Vec<(String, FileStats)> =
+            grouped.into_iter().map(|(label, stats)| (label.to_string(), stats)).collect();
+        grouped.sort_by(|a, b| b.1.files.cmp(&a.1.files).then_with(|| a.0.cmp(&b.0)));
+        grouped
+    }
+
+    /// Per-type stats ordered by file count descending, then type name, for stable output
+    pub fn sorted_file_stats(&self) -> Vec<(&str, &FileStats)> {
+        let mut stats: Vec<(&str, &FileStats)> = self
+            .file_stats
+            .iter()
+            .map(|(file_type, stats)| (file_type.as_str(), stats))
+            .collect();
+        stats.sort_by(|a, b| b.1.files.cmp(&a.1.files).then_with(|| a.0.cmp(b.0)));
+        stats
+    }
+
+    /// Number of files per detected file type, ordered by type name
+    pub fn type_counts(&self) -> std::collections::BTreeMap<&str, usize> {
+        self.file_stats
+            .iter()
+            .map(|(file_type, stats)| (file_type.as_str(), stats.files))
+            .collect()
+    }
+
+    /// Representative path per detected file type, ordered by type name
+    pub fn type_examples(&self) -> std::collections::BTreeMap<&str, &str> {
+        self.file_stats
+            .iter()
+            .filter_map(|(file_type, stats)| Some((file_type.as_str(), stats.example.as_deref()?)))
+            .collect()
+    }
+
+    /// Copies each file's git blob SHA from `tree` into its record, so runs can be
+    /// compared without refetching contents
+    pub fn attach_blob_shas(&mut self, tree: &[TreeNode]) {
+        let shas = blob_shas(tree);
+        for record in &mut self.files {
+            record.sha = shas.get(record.path.as_str()).map(|sha| sha.to_string());
+        }
+    }
+
+    /// Rewrites the reported paths for `--strip-prefix`; counts and findings are unchanged
+    pub fn strip_path_prefix(&mut self, prefix: &str) {
+        for record in &mut self.files {
+            record.path = strip_path_prefix(&record.path, prefix).to_string();
+        }
+        for skip in &mut self.skipped {
+            skip.path = strip_path_prefix(&skip.path, prefix).to_string();
+        }
+        for stats in self.file_stats.values_mut() {
+            if let Some(example) = &mut stats.example {
+                *example = strip_path_prefix(example, prefix).to_string();
             }
         }
     }
+
+    /// Unknown files grouped by extension (`.xyz`), or by file name when there is
+    /// none, as (key, count, first path); most frequent first
+    pub fn unknown_by_extension(&self) -> Vec<(String, usize, &str)> {
+        let mut groups: IndexMap<String, (usize, &str)> = IndexMap::new();
+        for record in self.files.iter().filter(|f| f.file_type == "Unknown") {
+            let name = file_name(&record.path);
+            let key = match name.rsplit_once('.') {
+                Some((stem, extension)) if !stem.is_empty() => format!(".{}", extension),
+                _ => name.to_string(),
+            };
+            groups.entry(key).or_insert((0, &record.path)).0 += 1;
+        }
+
+        let mut groups: Vec<(String, usize, &str)> =
+            groups.into_iter().map(|(key, (count, example))| (key, count, example)).collect();
+        groups.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        groups
+    }
+
+    /// Why content-based detection may be incomplete: files whose content was not
+    /// fetched (failures, `--deadline`) or `--sample`. Empty when it saw everything.
+    pub fn detection_limits(&self) -> Vec<String> {
+        let mut limits = Vec::new();
+        let unfetched = self
+            .skipped
+            .iter()
+            .filter(|skip| skip.reason != SYMLINK_SKIP_REASON)
+            .count();
+        if unfetched > 0 {
+            limits.push(format!(
+                "content not fetched for {} of {} files",
+                unfetched,
+                unfetched + self.files.len()
+            ));
+        } else if self.partial {
+            limits.push("deadline reached before all content was fetched".to_string());
+        }
+        if let Some(total) = self.sampled_from {
+            limits.push(format!("only {} of {} files sampled", self.files.len(), total));
+        }
+        limits
+    }
+
+    /// Share of the repository's bytes in images, video, audio, archives and fonts;
+    /// `None` when there are no bytes to compare
+    pub fn asset_share(&self) -> Option<f64> {
+        (self.total_bytes > 0).then(|| self.asset_bytes as f64 / self.total_bytes as f64)
+    }
+
+    pub fn is_asset_heavy(&self) -> bool {
+        self.asset_share().is_some_and(|share| share > ASSET_HEAVY_SHARE)
+    }
+
+    pub fn detection_confidence(&self) -> DetectionConfidence {
+        if self.detection_limits().is_empty() {
+            DetectionConfidence::Full
+        } else {
+            DetectionConfidence::Limited
+        }
+    }
+
+    /// Files flagged as generated, which `file_stats` leaves out
+    pub fn generated_count(&self) -> usize {
+        self.files.iter().filter(|f| f.generated).count()
+    }
+
+    /// Minified JS/CSS files, which `file_stats` leaves out
+    pub fn minified_count(&self) -> usize {
+        self.files.iter().filter(|f| f.minified).count()
+    }
+
+    /// Adds the skips reported while fetching to those found in the tree
+    pub fn record_skips(&mut self, skipped: Vec<SkipRecord>) {
+        self.skipped.extend(skipped);
+        self.skipped.sort_by(|a, b| a.path.cmp(&b.path));
+    }
+
+    pub fn findings_of(&self, kind: FindingKind) -> impl Iterator<Item = &Finding> {
+        self.findings.iter().filter(move |f| f.kind == kind)
+    }
 }
 
-fn fetch_sub_tree(url: &str) -> Result<GitTree, Box<dyn Error>> {
-    let client = reqwest::blocking::Client::new();
-    let tree_res = client.get(url).header(USER_AGENT, "rust-tool").send()?;
+/// Opening and closing marker of a block comment.
+type BlockComment = Option<(&'static str, &'static str)>;
 
-    if tree_res.status().is_success() {
-        let tree: GitTree = tree_res.json()?;
-        Ok(tree)
+/// Line- and block-comment markers per detected file type. Types not listed here
+/// only have blank lines excluded from their source line count.
+const COMMENT_SYNTAX: &[(&str, &[&str], BlockComment)] = &[
+    ("C", &["//"], Some(("/*", "*/"))),
+    ("C++", &["//"], Some(("/*", "*/"))),
+    ("C#", &["//"], Some(("/*", "*/"))),
+    ("Java", &["//"], Some(("/*", "*/"))),
+    ("JavaScript", &["//"], Some(("/*", "*/"))),
+    ("TypeScript", &["//"], Some(("/*", "*/"))),
+    ("Go", &["//"], Some(("/*", "*/"))),
+    ("Rust", &["//"], Some(("/*", "*/"))),
+    ("Swift", &["//"], Some(("/*", "*/"))),
+    ("Kotlin", &["//"], Some(("/*", "*/"))),
+    ("Scala", &["//"], Some(("/*", "*/"))),
+    ("Dart", &["//"], Some(("/*", "*/"))),
+    ("Groovy", &["//"], Some(("/*", "*/"))),
+    ("Objective-C", &["//"], Some(("/*", "*/"))),
+    ("PHP", &["//", "#"], Some(("/*", "*/"))),
+    ("Python", &["#"], None),
+    ("Ruby", &["#"], None),
+    ("Shell", &["#"], None),
+    ("Bash", &["#"], None),
+    ("PowerShell", &["#"], Some(("<#", "#>"))),
+    ("Perl", &["#"], None),
+    ("R", &["#"], None),
+    ("Elixir", &["#"], None),
+    ("CoffeeScript", &["#"], None),
+    ("Tcl", &["#"], None),
+    ("YAML", &["#"], None),
+    ("Terraform", &["#", "//"], Some(("/*", "*/"))),
+    ("SQL", &["--"], Some(("/*", "*/"))),
+    ("Haskell", &["--"], Some(("{-", "-}"))),
+    ("Lua", &["--"], None),
+    ("Ada", &["--"], None),
+    ("VHDL", &["--"], None),
+];
+
+/// Counts source lines: non-blank lines that are not comments. Block comments are
+/// only recognized when a line starts with the opening marker (best effort).
+pub fn count_sloc(file_type: &str, content: &str) -> usize {
+    let (line_markers, block) = COMMENT_SYNTAX
+        .iter()
+        .find(|(name, _, _)| *name == file_type)
+        .map_or((&[][..], None), |(_, line, block)| (*line, *block));
+
+    let mut in_block = false;
+    let mut sloc = 0;
+    for line in content.lines().map(str::trim) {
+        if in_block {
+            in_block = !block.is_some_and(|(_, close)| line.contains(close));
+            continue;
+        }
+        if line.is_empty() || line_markers.iter().any(|m| line.starts_with(m)) {
+            continue;
+        }
+        if let Some((open, close)) = block {
+            if let Some(rest) = line.strip_prefix(open) {
+                in_block = !rest.contains(close);
+                continue;
+            }
+        }
+        sloc += 1;
+    }
+    sloc
+}
+
+/// File name endings of common code generator output.
+const GENERATED_SUFFIXES: &[&str] = &[
+    ".pb.go", ".pb.cc", ".pb.h", "_pb2.py", "_pb2_grpc.py", ".g.dart", ".freezed.dart",
+    "_generated.rs", "_generated.go", ".generated.ts", ".designer.cs", ".g.cs",
+];
+
+/// Markers generators put in a file's header; `Code generated ... DO NOT EDIT.` is Go's standard.
+const GENERATED_MARKERS: &[&str] = &["Code generated", "DO NOT EDIT", "@generated", "<auto-generated"];
+
+/// Lines at the top of a file searched for a `GENERATED_MARKERS` entry.
+const GENERATED_HEADER_LINES: usize = 5;
+
+fn is_generated(path: &str, content: &str) -> bool {
+    let file_name = file_name(path);
+    GENERATED_SUFFIXES.iter().any(|suffix| file_name.ends_with(suffix))
+        || content
+            .lines()
+            .take(GENERATED_HEADER_LINES)
+            .any(|line| GENERATED_MARKERS.iter().any(|marker| line.contains(marker)))
+}
+
+const MINIFIABLE_TYPES: &[&str] = &["JavaScript", "CSS"];
+
+/// Average line length above which JS/CSS reads as minifier output; hand-written
+/// code stays well below it.
+const MINIFIED_AVG_LINE_LEN: usize = 250;
+
+/// Smaller files are never flagged, so a short one-line module isn't mistaken for a bundle.
+const MINIFIED_MIN_SIZE: usize = 1024;
+
+// Bundles are mostly one or a few very long lines
+fn is_minified(path: &str, file_type: &str, content: &str, lines: usize) -> bool {
+    if !MINIFIABLE_TYPES.contains(&file_type) {
+        return false;
+    }
+    let file_name = file_name(path);
+    file_name.ends_with(".min.js")
+        || file_name.ends_with(".min.css")
+        || (content.len() >= MINIFIED_MIN_SIZE && content.len() / lines.max(1) > MINIFIED_AVG_LINE_LEN)
+}
+
+fn file_record(path: &str, bytes: &[u8], mappings: &FileMappings) -> FileRecord {
+    let (mut file_type, mut category) = detect_file_type(path, mappings);
+    let content = String::from_utf8_lossy(bytes);
+    // Extensionless wrappers (bin/ scripts, hooks) are recognised by their shebang
+    if file_type == "Unknown" && !file_name(path).contains('.') {
+        if let Some(script_type) = sniff_script_type(&content) {
+            debug!("Detected file type: {} for file: {} (from its first line)", script_type, path);
+            category = mappings.category_of(script_type).unwrap_or("programming_languages");
+            file_type = script_type.to_string();
+        }
+    }
+    let lines = content.lines().count();
+    let minified = is_minified(path, &file_type, &content, lines);
+    FileRecord {
+        path: path.to_string(),
+        sloc: if minified { 0 } else { count_sloc(&file_type, &content) },
+        file_type,
+        category: category.to_string(),
+        size: bytes.len(),
+        lines,
+        generated: is_generated(path, &content),
+        minified,
+        sha: None,
+    }
+}
+
+fn blob_shas(tree: &[TreeNode]) -> HashMap<&str, &str> {
+    tree.iter()
+        .filter(|node| node.r#type == "blob")
+        .map(|node| (node.path.as_str(), node.sha.as_str()))
+        .collect()
+}
+
+fn analyze_files(
+    files: &HashMap<String, Vec<u8>>,
+    mappings: &FileMappings,
+) -> RepoAnalysis {
+    let mut file_stats = HashMap::new();
+    let mut file_records = Vec::new();
+
+    for (path, bytes) in files {
+        let record = file_record(path, bytes, mappings);
+
+        // Generated and minified files are reported on their own so the breakdown
+        // reflects hand-written code
+        if !record.is_hand_written() {
+            file_records.push(record);
+            continue;
+        }
+
+        // Update the file stats
+        let type_entry = file_stats.entry(record.file_type.clone()).or_insert_with(FileStats::new);
+        type_entry.record(&record.path, record.size);
+        file_records.push(record);
+    }
+
+    file_records.sort_by(|a, b| a.path.cmp(&b.path));
+
+    RepoAnalysis {
+        file_stats,
+        project_types: Vec::new(),
+        files: file_records,
+        findings: Vec::new(),
+        project_type: ProjectType::Unknown,
+        confidence: Confidence::Low,
+        depth: DepthStats::default(),
+        partial: false,
+        layout: Vec::new(),
+        sampled_from: None,
+        symlinks: 0,
+        executables: 0,
+        skipped: Vec::new(),
+        asset_bytes: 0,
+        total_bytes: 0,
+        type_groups: mappings.groups().clone(),
+        timings: Timings::default(),
+    }
+}
+
+const SHELL_TYPES: &[&str] = &["Shell", "Bash", "PowerShell"];
+
+/// Categories whose bytes count as binary assets rather than text.
+pub const ASSET_CATEGORIES: &[&str] = &["images", "video", "audio", "archives", "fonts"];
+
+/// Above this share of bytes in assets, a repository is more a data/asset store than a codebase.
+const ASSET_HEAVY_SHARE: f64 = 0.5;
+
+// Sizes come from the tree, so files whose content was not fetched still count;
+// without a tree (gists) the fetched contents are measured instead
+fn asset_bytes(tree: &[TreeNode], files: &HashMap<String, Vec<u8>>, mappings: &FileMappings) -> (u64, u64) {
+    let sizes: Vec<(&str, u64)> = if tree.is_empty() {
+        files.iter().map(|(path, bytes)| (path.as_str(), bytes.len() as u64)).collect()
     } else {
-        eprintln!(
-            "Failed to fetch the sub-tree: {} - {}",
-            tree_res.status(),
-            tree_res.text()?
-        );
-        Err("Failed to fetch the sub-tree".into())
+        tree.iter()
+            .filter(|node| node.r#type == "blob" && !node.is_symlink())
+            .map(|node| {
+                let size = node
+                    .size
+                    .or_else(|| files.get(&node.path).map(|bytes| bytes.len() as u64))
+                    .unwrap_or(0);
+                (node.path.as_str(), size)
+            })
+            .collect()
+    };
+
+    let mut assets = 0;
+    let mut total = 0;
+    for (path, size) in sizes {
+        let (_, category) = detect_file_type(path, mappings);
+        if ASSET_CATEGORIES.contains(&category) {
+            assets += size;
+        }
+        total += size;
+    }
+    (assets, total)
+}
+
+/// Share of source files that must be shell scripts for a Shell/Automation label.
+const SHELL_DOMINANCE: f64 = 0.5;
+
+// Ops/infra repos are mostly scripts plus configs and match no language-specific rule
+fn shell_automation_finding(files: &[FileRecord]) -> Option<Finding> {
+    let is_shell = |f: &&FileRecord| SHELL_TYPES.contains(&f.file_type.as_str());
+    let hand_written = || files.iter().filter(|f| f.is_hand_written());
+    let source = hand_written()
+        .filter(|f| f.category == "programming_languages" || is_shell(f))
+        .count();
+    let shell: Vec<String> = hand_written().filter(is_shell).map(|f| f.path.clone()).collect();
+
+    if source == 0 || (shell.len() as f64 / source as f64) <= SHELL_DOMINANCE {
+        return None;
+    }
+    Some(Finding::new(FindingKind::ProjectType, ProjectType::ShellAutomation.to_string()).with_evidence(shell))
+}
+
+/// File types that belong to one ecosystem; unlisted types are their own ecosystem.
+const ECOSYSTEMS: &[(&str, &str)] = &[
+    ("JavaScript", "JavaScript/TypeScript"),
+    ("TypeScript", "JavaScript/TypeScript"),
+    ("CoffeeScript", "JavaScript/TypeScript"),
+    ("C", "C/C++"),
+    ("C++", "C/C++"),
+    ("Java", "JVM"),
+    ("Kotlin", "JVM"),
+    ("Scala", "JVM"),
+    ("Groovy", "JVM"),
+    ("Clojure", "JVM"),
+    ("Shell", "Shell"),
+    ("Bash", "Shell"),
+];
+
+/// Build tooling rather than product code: `webpack.config.js`, `gulpfile.js`, `build.rs`, ...
+const BUILD_SCRIPT_NAMES: &[&str] = &[
+    "Gruntfile.js",
+    "gulpfile.js",
+    "build.rs",
+    "setup.py",
+    "noxfile.py",
+    "conftest.py",
+];
+
+/// Share of its bytes a build script contributes to its ecosystem's weight.
+const BUILD_SCRIPT_WEIGHT: f64 = 0.1;
+
+fn is_build_script(path: &str) -> bool {
+    let file_name = file_name(path);
+    BUILD_SCRIPT_NAMES.contains(&file_name) || file_name.contains(".config.")
+}
+
+/// The ecosystem most of the hand-written source belongs to, weighted by bytes.
+/// Generated files are ignored and build scripts discounted, so a stray build
+/// script doesn't outvote the actual code. Ties go to the ecosystem with more
+/// files, then to the alphabetically first.
+pub fn detect_primary_ecosystem(analysis: &RepoAnalysis) -> Option<&str> {
+    primary_ecosystem_share(analysis).map(|(ecosystem, _)| ecosystem)
+}
+
+/// The primary ecosystem (see `detect_primary_ecosystem`) with its share of the
+/// weighted source bytes, between 0 and 1.
+pub fn primary_ecosystem_share(analysis: &RepoAnalysis) -> Option<(&str, f64)> {
+    let mut weights: HashMap<&str, (f64, usize)> = HashMap::new();
+
+    let sources = analysis
+        .files
+        .iter()
+        .filter(|f| f.category == "programming_languages" && f.is_hand_written());
+    for record in sources {
+        let ecosystem = ECOSYSTEMS
+            .iter()
+            .find(|(file_type, _)| *file_type == record.file_type)
+            .map_or(record.file_type.as_str(), |(_, ecosystem)| ecosystem);
+        let factor = if is_build_script(&record.path) { BUILD_SCRIPT_WEIGHT } else { 1.0 };
+
+        let entry = weights.entry(ecosystem).or_default();
+        entry.0 += record.size as f64 * factor;
+        entry.1 += 1;
     }
-}
\ No newline at end of file
+
+    let total: f64 = weights.values().map(|(weight, _)| weight).sum();
+    weights
+        .into_iter()
+        .max_by(|(a_name, a), (b_name, b)| {
+            a.0.total_cmp(&b.0)
+                .then(a.1.cmp(&b.1))
+                .then_with(|| b_name.cmp(a_name))
+        })
+        .map(|(ecosystem, (weight, _))| (ecosystem, if total > 0.0 { weight / total } else { 0.0 }))
+}
+
+pub async fn analyze_repo(
+    tree: &[TreeNode],
+    files: &HashMap<String, Vec<u8>>,
+    mappings: &FileMappings,
+    registry: &DetectorRegistry,
+) -> RepoAnalysis {
+    let started = Instant::now();
+    let mut analysis = analyze_files(files, mappings);
+    analysis.findings = registry.run(tree, files);
+    analysis.findings.extend(shell_automation_finding(&analysis.files));
+    analysis.depth = DepthStats::from_tree(tree);
+    analysis.layout = top_level_layout(tree);
+    analysis.symlinks = tree.iter().filter(|node| node.is_symlink()).count();
+    analysis.executables = tree.iter().filter(|node| node.is_executable()).count();
+    (analysis.asset_bytes, analysis.total_bytes) = asset_bytes(tree, files, mappings);
+    analysis.skipped = tree
+        .iter()
+        .filter(|node| node.is_symlink())
+        .map(|node| SkipRecord::new(&node.path, SYMLINK_SKIP_REASON))
+        .collect();
+    // Strongest evidence first, then by name, so the list is the same on every run
+    let mut project_types: Vec<(u32, &str)> = analysis
+        .findings_of(FindingKind::ProjectType)
+        .map(|f| (indicator_score(f), f.label.as_str()))
+        .collect();
+    project_types.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(b.1)));
+    analysis.project_types = project_types
+        .into_iter()
+        .map(|(_, label)| ProjectType::from_label(label))
+        .collect();
+
+    let project_findings: Vec<&Finding> = analysis.findings_of(FindingKind::ProjectType).collect();
+    let (project_type, confidence) = detect_combined_project_type(&project_findings);
+    analysis.project_type = project_type;
+    analysis.confidence = confidence;
+    analysis.timings = Timings {
+        analysis: started.elapsed(),
+        files: files.len(),
+        ..Timings::default()
+    };
+    analysis
+}
+
+fn build_registry(options: &Options) -> DetectorRegistry {
+    let mut registry = DetectorRegistry::with_defaults();
+    // SARIF exists to carry findings like these, so it always includes the scan
+    if options.scan_secrets || options.format == OutputFormat::Sarif {
+        registry.register(Box::new(crate::secrets::SecretDetector));
+    }
+    if options.list_deps {
+        registry.register(Box::new(crate::deps::DependencyDetector));
+    }
+    if options.check_licenses {
+        registry.register(Box::new(crate::licenses::LicenseConflictDetector));
+    }
+    registry
+}
+
+fn display_analysis(analysis: &RepoAnalysis, options: &Options) {
+    if analysis.partial {
+        println!("Deadline reached, results partial");
+    }
+
+    if options.stats_only {
+        display_file_stats(analysis, options);
+        if options.explain_unknown {
+            display_unknown(analysis);
+        }
+        return;
+    }
+
+    crate::display::display_readme(analysis.findings_of(FindingKind::Readme).next());
+    crate::display::display_changelog(analysis.findings_of(FindingKind::Changelog).next());
+    display_file_stats(analysis, options);
+    crate::display::display_findings(&analysis.findings);
+    if options.explain {
+        crate::display::display_evidence(&analysis.findings);
+    }
+    if options.explain_unknown {
+        display_unknown(analysis);
+    }
+
+    if options.scan_secrets {
+        let secrets: Vec<&Finding> = analysis.findings_of(FindingKind::Secret).collect();
+        crate::secrets::display_secrets(&secrets);
+    }
+
+    if options.list_deps {
+        let dependencies: Vec<&Finding> = analysis.findings_of(FindingKind::Dependency).collect();
+        crate::deps::display_dependencies(&dependencies);
+    }
+
+    if options.check_licenses {
+        let conflicts: Vec<&Finding> = analysis.findings_of(FindingKind::LicenseConflict).collect();
+        crate::licenses::display_license_conflicts(&conflicts);
+    }
+
+    crate::health::display_health(&crate::health::compute_health(analysis));
+}
+fn detect_combined_project_type(findings: &[&Finding]) -> (ProjectType, Confidence) {
+    let detected: Vec<(ProjectType, u32)> = findings
+        .iter()
+        .map(|f| (ProjectType::from_label(&f.label), indicator_score(f)))
+        .collect();
+    let score_of = |project_type: &ProjectType| {
+        detected
+            .iter()
+            .find(|(detected, _)| detected == project_type)
+            .map(|(_, score)| *score)
+    };
+
+    // Every detected type is a candidate on its own; a website and a backend combine
+    let singles = detected.iter().cloned();
+    let combinations = score_of(&ProjectType::Website).into_iter().flat_map(|website| {
+        ProjectType::BACKENDS.iter().filter_map(move |backend| {
+            let backend_score = score_of(backend)?;
+            Some((ProjectType::WebsiteWith(Box::new(backend.clone())), website + backend_score))
+        })
+    });
+
+    let mut best: Option<(ProjectType, u32)> = None;
+    for (project_type, score) in combinations.chain(singles) {
+        if best.as_ref().is_none_or(|(_, best_score)| score > *best_score) {
+            best = Some((project_type, score));
+        }
+    }
+
+    match best {
+        Some((project_type, score)) => (project_type, Confidence::from_score(score)),
+        None => (ProjectType::Unknown, Confidence::Low),
+    }
+}
+
+// --explain-unknown: what extensions.json is missing, most common first
+fn display_unknown(analysis: &RepoAnalysis) {
+    let groups = analysis.unknown_by_extension();
+    if groups.is_empty() {
+        println!("Unknown files: none");
+        return;
+    }
+
+    println!("Unknown files by extension:");
+    for (key, count, example) in groups {
+        println!("  {}: {} (e.g. {})", key, count, example);
+    }
+}
+
+fn display_file_stats(analysis: &RepoAnalysis, options: &Options) {
+    match analysis.sampled_from {
+        Some(total) => println!("Repository contents (sampled {} of {} files):", analysis.files.len(), total),
+        None => println!("Repository contents:"),
+    }
+    println!("--------------------------------------------------");
+
+    let file_stats: Vec<(String, FileStats)> = if options.group {
+        analysis.grouped_file_stats()
+    } else {
+        analysis
+            .sorted_file_stats()
+            .into_iter()
+            .map(|(file_type, stats)| (file_type.to_string(), stats.clone()))
+            .collect()
+    };
+    for (file_type, stats) in &file_stats {
+        println!("File Type: {}", crate::display::paint_file_type(file_type));
+        match stats.example.as_deref().filter(|_| options.examples) {
+            Some(example) => println!("Files: {} (e.g. {})", stats.files, example),
+            None => println!("Files: {}", stats.files),
+        }
+        println!("--------------------------------------------------");
+    }
+
+    let generated = analysis.generated_count();
+    if generated > 0 {
+        println!("Generated files: {} (not counted above)", generated);
+    }
+    let minified = analysis.minified_count();
+    if minified > 0 {
+        println!("Minified files: {} (not counted above)", minified);
+    }
+
+    let lines: usize = analysis.files.iter().map(|f| f.lines).sum();
+    let sloc: usize = analysis.files.iter().map(|f| f.sloc).sum();
+    println!("Lines: {} ({} source, excluding blanks and comments)", lines, sloc);
+
+    if let Some(share) = analysis.asset_share().filter(|_| analysis.asset_bytes > 0) {
+        if analysis.is_asset_heavy() {
+            println!("Assets: {:.0}% of repo size (asset-heavy)", share * 100.0);
+        } else {
+            println!("Assets: {:.0}% of repo size", share * 100.0);
+        }
+    }
+
+    if analysis.symlinks > 0 || analysis.executables > 0 {
+        println!(
+            "Symlinks: {}, executables: {}",
+            analysis.symlinks, analysis.executables
+        );
+    }
+
+    if analysis.depth.max > 0 {
+        println!(
+            "Max depth: {}, avg: {:.1}",
+            analysis.depth.max, analysis.depth.average
+        );
+    }
+
+    if !analysis.layout.is_empty() {
+        let dirs: Vec<String> = analysis
+            .layout
+            .iter()
+            .map(|dir| match dir.role {
+                Some(role) => format!("{}/ ({})", dir.name, role),
+                None => format!("{}/", dir.name),
+            })
+            .collect();
+        println!("Layout: {}", dirs.join(", "));
+    }
+    
+    if let Some(ecosystem) = detect_primary_ecosystem(analysis) {
+        println!("Primary ecosystem: {}", crate::display::paint_headline(ecosystem));
+    }
+
+    println!(
+        "Detected Project Type: {} ({} confidence)",
+        crate::display::paint_headline(&analysis.project_type.to_string()),
+        analysis.confidence
+    );
+    let limits = analysis.detection_limits();
+    if !limits.is_empty() {
+        println!("Project-type detection limited: {}", limits.join("; "));
+    }
+}
+
+#[derive(Deserialize)]
+struct GitBlob {
+    content: String,
+    encoding: String,
+}
+
+impl GitBlob {
+    fn decode(self) -> Result<Vec<u8>, Box<dyn Error>> {
+        match self.encoding.as_str() {
+            // GitHub wraps the base64 payload at 60 columns
+            "base64" => {
+                let encoded: String = self.content.split_whitespace().collect();
+                Ok(BASE64.decode(encoded)?)
+            }
+            "utf-8" => Ok(self.content.into_bytes()),
+            // The Contents API leaves files over 1 MB out of the response
+            "none" => Err("content not included (over 1 MB); use --fetch-strategy blob".into()),
+            other => Err(format!("Unsupported blob encoding: {}", other).into()),
+        }
+    }
+}
+
+const RAW_CONTENT_BASE: &str = "https://raw.githubusercontent.com";
+
+/// `--fetch-strategy contents|raw`: points every file at that endpoint for `git_ref`
+/// instead of at its git blob. The blob SHA is kept, so the blob cache still applies.
+fn retarget_files(nodes: &mut [TreeNode], strategy: FetchStrategy, owner: &str, repo: &str, git_ref: &str) {
+    let (base, query) = match strategy {
+        FetchStrategy::Contents => (format!("https://api.github.com/repos/{}/{}/contents", owner, repo), true),
+        FetchStrategy::Raw => (format!("{}/{}/{}/{}", RAW_CONTENT_BASE, owner, repo, git_ref), false),
+        FetchStrategy::Blob | FetchStrategy::Archive => return,
+    };
+    let Ok(base) = reqwest::Url::parse(&base) else {
+        return;
+    };
+
+    for node in nodes.iter_mut().filter(|node| node.is_file()) {
+        let mut url = base.clone();
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments.extend(node.path.split('/'));
+        }
+        if query {
+            url.query_pairs_mut().append_pair("ref", git_ref);
+        }
+        node.url = Some(url.to_string());
+    }
+}
+
+async fn fetch_files(client: &GitHubClient, tree: &[TreeNode]) -> Result<FetchedFiles, Box<dyn Error>> {
+    fetch_files_streaming(client, tree, &mut |_, _| {}).await
+}
+
+// `on_file` sees every file as soon as it is fetched, for streaming output formats.
+// A file that cannot be fetched is logged and skipped so the rest still gets analyzed.
+async fn fetch_files_streaming(
+    client: &GitHubClient,
+    tree: &[TreeNode],
+    on_file: &mut (dyn FnMut(&str, &[u8]) + Send),
+) -> Result<FetchedFiles, Box<dyn Error>> {
+    Ok(fetch_until(tree, client.deadline(), |node| fetch_file(client, node), on_file).await)
+}
+
+/// Fetches every file of `tree` with `fetch`. `--deadline` is a single
+/// `tokio::time::timeout` around this whole fetch phase rather than around
+/// `analyze_repo`: classifying fetched files is synchronous and takes a fraction of
+/// the run, so a timeout could not interrupt it, and cancelling it would throw away
+/// everything fetched so far. Files not reached in time are recorded as skipped.
+async fn fetch_until<'t, Fut>(
+    tree: &'t [TreeNode],
+    deadline: Option<tokio::time::Instant>,
+    mut fetch: impl FnMut(&'t TreeNode) -> Fut,
+    on_file: &mut (dyn FnMut(&str, &[u8]) + Send),
+) -> FetchedFiles
+where
+    Fut: std::future::Future<Output = Result<Option<Vec<u8>>, Box<dyn Error>>>,
+{
+    let nodes: Vec<&TreeNode> = tree.iter().filter(|node| node.is_file()).collect();
+    let mut files = HashMap::new();
+    let mut skipped = Vec::new();
+    let mut failed = 0;
+    let mut done = 0;
+
+    let fetch_all = async {
+        for node in &nodes {
+            match fetch(node).await {
+                Ok(Some(content)) => {
+                    on_file(&node.path, &content);
+                    files.insert(node.path.clone(), content);
+                }
+                Ok(None) => skipped.push(SkipRecord::new(&node.path, "no download URL")),
+                Err(e) => {
+                    warn!("Skipping {}: {}", node.path, e);
+                    skipped.push(SkipRecord::new(&node.path, format!("fetch failed: {}", e)));
+                    failed += 1;
+                }
+            }
+            done += 1;
+        }
+    };
+    let finished = match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline, fetch_all).await.is_ok(),
+        None => {
+            fetch_all.await;
+            true
+        }
+    };
+
+    // The file in flight when the deadline passed is skipped along with the rest
+    if !finished {
+        skipped.extend(nodes[done..].iter().map(|node| SkipRecord::new(&node.path, "deadline reached")));
+        warn!("Deadline reached after fetching {} files; results are partial", files.len());
+    }
+
+    if failed > 0 {
+        warn!(
+            "{} {} could not be fetched",
+            failed,
+            if failed == 1 { "file" } else { "files" }
+        );
+    }
+
+    (files, skipped)
+}
+
+#[instrument(skip_all, fields(path = %node.path))]
+pub(crate) async fn fetch_file(client: &GitHubClient, node: &TreeNode) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+    let cache = client.blob_cache();
+    if let Some(content) = cache.and_then(|cache| cache.get(&node.sha)) {
+        debug!("Blob cache hit");
+        return Ok(Some(content));
+    }
+
+    let url = match &node.url {
+        Some(url) => url,
+        None => {
+            warn!("Skipping file due to missing URL");
+            return Ok(None);
+        }
+    };
+
+    debug!("Fetching blob");
+    let file_res = client.get_with_retry(url).await?;
+
+    let status = file_res.status();
+    if !status.is_success() {
+        let body = file_res.text().await.unwrap_or_default();
+        return Err(format!("Failed to fetch file: {} - {}", status, body.trim()).into());
+    }
+
+    // Raw downloads are the bytes themselves; the blob and Contents APIs wrap them in JSON
+    let content = if url.starts_with(RAW_CONTENT_BASE) {
+        file_res.bytes().await?.to_vec()
+    } else {
+        parse_json::<GitBlob>(file_res).await?.decode()?
+    };
+    if let Some(cache) = cache {
+        if let Err(e) = cache.put(&node.sha, &content) {
+            warn!("Could not cache blob {}: {}", node.sha, e);
+        }
+    }
+    Ok(Some(content))
+}
+
+/// Deserializes a response body, keeping the status and a body snippet on failure
+/// so that HTML error pages don't surface as bare serde errors.
+pub(crate) async fn parse_json<T: DeserializeOwned>(res: reqwest::Response) -> Result<T, ApiError> {
+    let status = res.status();
+    let body = res.text().await?;
+    serde_json::from_str(&body).map_err(|e| ApiError::parse(status, &body, e))
+}
+
+pub async fn fetch_and_display_tree(
+    github_url: &str,
+    options: &Options,
+) -> Result<(), Box<dyn Error>> {
+    let local = Path::new(github_url);
+    if local.is_dir() {
+        return fetch_and_display_local(local, options).await;
+    }
+    if options.write_report {
+        warn!("--write-report only applies to local directories; ignoring it");
+    }
+
+    if let Some(gist_id) = extract_gist_id(github_url) {
+        return fetch_and_display_gist(&gist_id, options).await;
+    }
+
+    let (host, mut owner, mut repo) = extract_owner_repo(github_url)?;
+    if host != Host::GitHub {
+        // The GitHub token must not be sent to other hosts
+        let config = ClientConfig {
+            token: None,
+            ..ClientConfig::from_options(options)
+        };
+        let client = GitHubClient::new(&config)?;
+        let name = format!("{}/{}", owner, repo);
+        return match host {
+            Host::GitLab => fetch_and_display_forge(&GitLabForge::new(&client, &owner, &repo), &name, options).await,
+            _ => fetch_and_display_forge(&BitbucketForge::new(&client, &owner, &repo), &name, options).await,
+        };
+    }
+
+    let client = GitHubClient::new(&ClientConfig::from_options(options))?;
+    let filter = PathFilter::from_options(options)?;
+
+    let mappings = load_file_mappings()?;
+
+    // Fetch repository info; not-found, forbidden and legal blocks surface as typed errors
+    let repo_info = fetch_repo_info(&client, &owner, &repo).await?;
+
+    // GitHub redirects renamed or transferred repos; continue at the new location
+    if let Some((new_owner, new_repo)) = repo_info.moved_from(&owner, &repo) {
+        warn!("Repository moved to {}/{}", new_owner, new_repo);
+        owner = new_owner;
+        repo = new_repo;
+    }
+    let mut default_branch = options
+        .branch
+        .clone()
+        .unwrap_or_else(|| repo_info.default_branch.clone());
+    if is_short_sha(&default_branch) {
+        default_branch = resolve_short_sha(&client, &owner, &repo, &default_branch).await?;
+    }
+    if let Some(release) = &options.release {
+        let release = fetch_release(&client, &owner, &repo, release).await?;
+        if options.format == OutputFormat::Text {
+            println!("Release: {}", release);
+        }
+        default_branch = release.tag_name;
+    }
+
+    if options.wiki {
+        return fetch_and_display_wiki(&owner, &repo, options).await;
+    }
+
+    if let Some(number) = options.pr {
+        return fetch_and_display_pull_request(&client, &owner, &repo, number, &mappings, options).await;
+    }
+
+    if let Some(base) = &options.since {
+        return fetch_and_display_changes(&client, &owner, &repo, base, &default_branch, &mappings, options)
+            .await;
+    }
+
+    // Fetch tree
+    let tree_started = Instant::now();
+    let mut tree_res = fetch_tree(&client, &owner, &repo, &default_branch).await?;
+
+    // A 404 here usually means the branch was just renamed or hasn't replicated yet;
+    // confirm the default branch once and retry if it moved
+    if tree_res.status() == reqwest::StatusCode::NOT_FOUND && options.branch.is_none() && options.release.is_none() {
+        let current = fetch_repo_info(&client, &owner, &repo).await?.default_branch;
+        if current != default_branch {
+            warn!(
+                "Default branch changed from '{}' to '{}'; retrying",
+                default_branch, current
+            );
+            default_branch = current;
+            tree_res = fetch_tree(&client, &owner, &repo, &default_branch).await?;
+        }
+    }
+
+    // Opt-in: try the conventional names, then whatever the repository reports
+    if tree_res.status() == reqwest::StatusCode::NOT_FOUND && options.branch_fallback {
+        let mut tried = vec![default_branch.clone()];
+        for candidate in ["main", "master", repo_info.default_branch.as_str()] {
+            if tried.iter().any(|branch| branch == candidate) {
+                continue;
+            }
+            tried.push(candidate.to_string());
+
+            let res = fetch_tree(&client, &owner, &repo, candidate).await?;
+            if res.status() != reqwest::StatusCode::NOT_FOUND {
+                warn!("Branch '{}' not found; using '{}' instead", default_branch, candidate);
+                default_branch = candidate.to_string();
+                tree_res = res;
+                break;
+            }
+        }
+    }
+
+    if tree_res.status().is_success() {
+        let mut tree: GitTree = parse_json(tree_res).await?;
+        if tree.truncated {
+            warn!("Tree listing was truncated; walking directories individually");
+            tree.tree = walk_tree(&client, &tree.url, options.concurrency, options.format == OutputFormat::Text).await?;
+        }
+        let tree_elapsed = tree_started.elapsed();
+        let (mut nodes, sampled_from) = apply_sample(filter.apply(&tree.tree), options);
+        if options.dry_run {
+            return report_dry_run(&client, &nodes, options).await;
+        }
+
+        let strategy = options.resolved_fetch_strategy();
+        retarget_files(&mut nodes, strategy, &owner, &repo, &default_branch);
+        let content_started = Instant::now();
+        let archived = if strategy == FetchStrategy::Archive {
+            match crate::archive::fetch_archive(&client, &owner, &repo, &default_branch).await {
+                Ok(files) => Some(archive_files(files, &nodes)),
+                Err(e) => {
+                    warn!("Archive download failed, falling back to per-file requests: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        // Fetch file contents
+        if archived.is_none() && options.format == OutputFormat::Ndjson {
+            let files = stream_ndjson(&client, &nodes, &mappings, sampled_from, options).await?;
+            if options.include_submodules {
+                analyze_submodules(&files, options).await;
+            }
+            return Ok(());
+        }
+
+        let (files, skipped) = match archived {
+            Some(fetched) => fetched,
+            None => fetch_files(&client, &nodes).await?,
+        };
+        let content_elapsed = content_started.elapsed();
+        let mut analysis = analyze_repo(&nodes, &files, &mappings, &build_registry(options)).await;
+        analysis.record_skips(skipped);
+        analysis.partial = client.deadline_reached();
+        analysis.sampled_from = sampled_from;
+        if options.include_content_hash {
+            analysis.attach_blob_shas(&nodes);
+        }
+        analysis.timings.tree = tree_elapsed;
+        analysis.timings.content = content_elapsed;
+
+        output_analysis(&format!("{}/{}", owner, repo), &nodes, &analysis, options)?;
+
+        if options.include_submodules {
+            analyze_submodules(&files, options).await;
+        }
+
+    } else if tree_res.status() == reqwest::StatusCode::NOT_FOUND {
+        // The repository exists (its info was fetched), so a 404 is about the branch
+        return Err(format!(
+            "Failed to fetch the repo tree for branch '{}': {} - {}",
+            default_branch,
+            tree_res.status(),
+            tree_res.text().await?
+        )
+        .into());
+    } else {
+        let name = format!("{}/{}", owner, repo);
+        return Err(repo_request_error(tree_res, &name, "Failed to fetch the repo tree").await);
+    }
+
+    Ok(())
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct RepoInfo {
+    full_name: String,
+    #[serde(default = "default_branch_name")]
+    pub(crate) default_branch: String,
+}
+
+fn default_branch_name() -> String {
+    "main".to_string()
+}
+
+impl RepoInfo {
+    /// The new owner/repo when the response describes a different repository than
+    /// the one requested, i.e. the request was redirected after a rename or transfer.
+    fn moved_from(&self, owner: &str, repo: &str) -> Option<(String, String)> {
+        let (new_owner, new_repo) = self.full_name.split_once('/')?;
+        let requested = format!("{}/{}", owner, repo);
+        (!self.full_name.eq_ignore_ascii_case(&requested))
+            .then(|| (new_owner.to_string(), new_repo.to_string()))
+    }
+}
+
+pub(crate) async fn fetch_repo_info(
+    client: &GitHubClient,
+    owner: &str,
+    repo: &str,
+) -> Result<RepoInfo, Box<dyn Error>> {
+    let repo_url = format!("https://api.github.com/repos/{}/{}", owner, repo);
+    let repo_res = client.get(&repo_url).send().await?;
+
+    if !repo_res.status().is_success() {
+        let name = format!("{}/{}", owner, repo);
+        return Err(repo_request_error(repo_res, &name, "Failed to fetch repository info").await);
+    }
+
+    Ok(parse_json(repo_res).await?)
+}
+
+/// Error for an unsuccessful repository request: 403/404/451 become a typed
+/// `ApiError` with an actionable message, anything else keeps the raw body.
+async fn repo_request_error(res: reqwest::Response, repo: &str, context: &str) -> Box<dyn Error> {
+    let status = res.status();
+    let headers = res.headers().clone();
+    let body = match res.text().await {
+        Ok(body) => body,
+        Err(e) => return ApiError::Http(e).into(),
+    };
+
+    match ApiError::from_status(status, &headers, repo, &body) {
+        Some(e) => e.into(),
+        None => format!("{}: {} - {}", context, status, body).into(),
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CommitRef {
+    pub(crate) sha: String,
+}
+
+/// Abbreviated commit SHAs, which the tree endpoint does not resolve on its own.
+pub(crate) fn is_short_sha(git_ref: &str) -> bool {
+    (7..40).contains(&git_ref.len()) && git_ref.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Resolves a short SHA to the full one through the commits endpoint.
+pub(crate) async fn resolve_short_sha(
+    client: &GitHubClient,
+    owner: &str,
+    repo: &str,
+    short_sha: &str,
+) -> Result<String, Box<dyn Error>> {
+    let url = format!("https://api.github.com/repos/{}/{}/commits/{}", owner, repo, short_sha);
+    let res = client.get(&url).send().await?;
+    let status = res.status();
+
+    if status.is_success() {
+        let commit: CommitRef = parse_json(res).await?;
+        info!("Resolved {} to {}", short_sha, commit.sha);
+        return Ok(commit.sha);
+    }
+
+    let body = res.text().await?;
+    // 422 covers both unknown and ambiguous prefixes; GitHub's message says which
+    if status == reqwest::StatusCode::NOT_FOUND || status == reqwest::StatusCode::UNPROCESSABLE_ENTITY {
+        let message = serde_json::from_str::<serde_json::Value>(&body)
+            .ok()
+            .and_then(|json| json["message"].as_str().map(str::to_string))
+            .unwrap_or(body);
+        return Err(format!("Cannot resolve commit '{}': {}", short_sha, message).into());
+    }
+    Err(format!("Failed to resolve commit '{}': {} - {}", short_sha, status, body).into())
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct Release {
+    pub(crate) tag_name: String,
+    name: Option<String>,
+    published_at: Option<String>,
+}
+
+impl std::fmt::Display for Release {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.tag_name)?;
+        if let Some(name) = self.name.as_deref().filter(|name| !name.is_empty() && *name != self.tag_name) {
+            write!(f, " \"{}\"", name)?;
+        }
+        if let Some(published_at) = &self.published_at {
+            // Only the date part of the RFC 3339 timestamp
+            write!(f, " (published {})", published_at.split('T').next().unwrap_or(published_at))?;
+        }
+        Ok(())
+    }
+}
+
+/// Looks up `--release`: `latest` is the most recent non-prerelease, anything else a tag.
+/// The release is analyzed at its tag, since `target_commitish` only names the branch
+/// the tag was cut from and may have moved on since.
+pub(crate) async fn fetch_release(
+    client: &GitHubClient,
+    owner: &str,
+    repo: &str,
+    release: &str,
+) -> Result<Release, Box<dyn Error>> {
+    let url = if release == "latest" {
+        format!("https://api.github.com/repos/{}/{}/releases/latest", owner, repo)
+    } else {
+        format!("https://api.github.com/repos/{}/{}/releases/tags/{}", owner, repo, release)
+    };
+    let res = client.get(&url).send().await?;
+
+    if res.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(match release {
+            "latest" => format!("{}/{} has no published releases", owner, repo),
+            tag => format!("No release tagged '{}' in {}/{}", tag, owner, repo),
+        }
+        .into());
+    }
+    if !res.status().is_success() {
+        let name = format!("{}/{}", owner, repo);
+        return Err(repo_request_error(res, &name, "Failed to fetch release").await);
+    }
+
+    Ok(parse_json(res).await?)
+}
+
+pub(crate) async fn fetch_tree(
+    client: &GitHubClient,
+    owner: &str,
+    repo: &str,
+    branch: &str,
+) -> reqwest::Result<reqwest::Response> {
+    let tree_url = format!(
+        "https://api.github.com/repos/{}/{}/git/trees/{}?recursive=1",
+        owner, repo, branch
+    );
+    info!("Tree URL: {}", tree_url);
+    client.get(&tree_url).send().await
+}
+
+fn output_analysis(
+    name: &str,
+    nodes: &[TreeNode],
+    analysis: &RepoAnalysis,
+    options: &Options,
+) -> Result<(), Box<dyn Error>> {
+    if wants_tree(options) {
+        show_tree(nodes, options);
+    }
+    match options.format {
+        OutputFormat::Text if options.tui => {
+            crate::tui::run(&display_nodes(nodes, options), &display_view(analysis, options))?
+        }
+        OutputFormat::Text => display_analysis(&display_view(analysis, options), options),
+        _ => write_machine_output(name, analysis, options)?,
+    }
+    // stderr, so machine-readable output on stdout stays parseable
+    if options.timings {
+        eprintln!("Timings: {}", analysis.timings);
+    }
+    report_skips(analysis, options);
+    Ok(())
+}
+
+// --debug-skips: why each file missing from the counts was not analyzed (to stderr)
+fn report_skips(analysis: &RepoAnalysis, options: &Options) {
+    if !options.debug_skips {
+        return;
+    }
+    if analysis.skipped.is_empty() {
+        eprintln!("Skipped files: none");
+        return;
+    }
+
+    eprintln!("Skipped files ({}):", analysis.skipped.len());
+    for skip in &analysis.skipped {
+        eprintln!("  {}: {}", skip.path, skip.reason);
+    }
+}
+
+/// Runs the standard pipeline against a non-GitHub host: only fetching differs.
+async fn fetch_and_display_forge<F: Forge>(forge: &F, name: &str, options: &Options) -> Result<(), Box<dyn Error>> {
+    let (nodes, analysis) = analyze_forge(forge, options).await?;
+    output_analysis(name, &nodes, &analysis, options)
+}
+
+/// Analyzes a checkout on disk; with `--write-report` the summary is also saved into it.
+async fn fetch_and_display_local(root: &Path, options: &Options) -> Result<(), Box<dyn Error>> {
+    let (nodes, analysis) = analyze_forge(&LocalForge::new(root), options).await?;
+    output_analysis(&root.display().to_string(), &nodes, &analysis, options)?;
+
+    if options.write_report {
+        let path = crate::export::write_markdown_report(root, &analysis, options.force)?;
+        eprintln!("Wrote {}", path.display());
+    }
+    Ok(())
+}
+
+/// Markup formats GitHub renders as wiki pages.
+const WIKI_PAGE_EXTENSIONS: &[&str] = &[
+    "md", "markdown", "mediawiki", "wiki", "textile", "rdoc", "org", "creole", "pod", "asciidoc", "adoc", "rst",
+];
+
+// Wikis are separate git repositories that the REST API does not serve, so the
+// wiki is shallow-cloned and run through the local-directory pipeline
+async fn fetch_and_display_wiki(owner: &str, repo: &str, options: &Options) -> Result<(), Box<dyn Error>> {
+    let url = format!("https://github.com/{}/{}.wiki.git", owner, repo);
+    let checkout = env::temp_dir().join(format!("projectchecker-wiki-{}-{}-{}", owner, repo, std::process::id()));
+    info!("Cloning wiki: {}", url);
+
+    let status = tokio::process::Command::new("git")
+        .args(["clone", "--quiet", "--depth", "1", &url])
+        .arg(&checkout)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .status()
+        .await
+        .map_err(|e| format!("Failed to run git to clone the wiki: {}", e))?;
+    if !status.success() {
+        let _ = std::fs::remove_dir_all(&checkout);
+        return Err(format!("{}/{} has no wiki, or it has no pages yet", owner, repo).into());
+    }
+
+    let result = analyze_forge(&LocalForge::new(&checkout), options).await;
+    let _ = std::fs::remove_dir_all(&checkout);
+    let (nodes, analysis) = result?;
+
+    if options.format == OutputFormat::Text {
+        let pages: Vec<&FileRecord> = analysis
+            .files
+            .iter()
+            .filter(|record| {
+                record
+                    .path
+                    .rsplit_once('.')
+                    .is_some_and(|(_, ext)| WIKI_PAGE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+            })
+            .collect();
+        let size: usize = pages.iter().map(|record| record.size).sum();
+        println!("Wiki: {} pages, {:.1} KB", pages.len(), size as f64 / 1024.0);
+    }
+    output_analysis(&format!("{}/{}.wiki", owner, repo), &nodes, &analysis, options)
+}
+
+/// Fetches and analyzes a repository through `forge` without printing anything.
+/// Returns the filtered tree alongside the analysis.
+pub async fn analyze_forge<F: Forge>(
+    forge: &F,
+    options: &Options,
+) -> Result<(Vec<TreeNode>, RepoAnalysis), Box<dyn Error>> {
+    let filter = PathFilter::from_options(options)?;
+    let mappings = load_file_mappings()?;
+
+    let branch = match &options.branch {
+        Some(branch) => branch.clone(),
+        None => forge.default_branch().await?,
+    };
+    let tree_started = Instant::now();
+    let tree = forge.fetch_tree(&branch).await?;
+    let tree_elapsed = tree_started.elapsed();
+    let (nodes, sampled_from) = apply_sample(filter.apply(&tree), options);
+
+    let content_started = Instant::now();
+    let (files, skipped) = forge.fetch_files(&nodes).await;
+    let content_elapsed = content_started.elapsed();
+
+    let mut analysis = analyze_repo(&nodes, &files, &mappings, &build_registry(options)).await;
+    analysis.record_skips(skipped);
+    analysis.sampled_from = sampled_from;
+    if options.include_content_hash {
+        analysis.attach_blob_shas(&nodes);
+    }
+    analysis.timings.tree = tree_elapsed;
+    analysis.timings.content = content_elapsed;
+    Ok((nodes, analysis))
+}
+
+// --sample: returns the sampled tree and the number of files it was drawn from
+fn apply_sample(nodes: Vec<TreeNode>, options: &Options) -> (Vec<TreeNode>, Option<usize>) {
+    match options.sample {
+        Some(count) => {
+            let total = nodes.iter().filter(|node| node.is_file()).count();
+            (crate::filter::sample_blobs(&nodes, count, options.seed), Some(total))
+        }
+        None => (nodes, None),
+    }
+}
+
+/// Lists a tree one directory at a time, with up to `concurrency` directory
+/// requests in flight. Used when the recursive listing comes back truncated.
+/// With `progress`, a live counter is drawn on stderr when it is a terminal.
+pub(crate) async fn walk_tree(
+    client: &GitHubClient,
+    root_url: &str,
+    concurrency: usize,
+    progress: bool,
+) -> Result<Vec<TreeNode>, Box<dyn Error>> {
+    let client = client.clone();
+    walk_tree_with(root_url, concurrency, progress, move |url| {
+        let client = client.clone();
+        async move {
+            let res = client.get_with_retry(&url).await.map_err(|e| e.to_string())?;
+            if !res.status().is_success() {
+                return Err(format!("Failed to fetch tree {}: {}", url, res.status()));
+            }
+            parse_json(res).await.map_err(|e| e.to_string())
+        }
+    })
+    .await
+}
+
+// `walk_tree` with the directory listing behind `fetch_tree`, so the walk itself can
+// be exercised without the API
+async fn walk_tree_with<F, Fut>(
+    root_url: &str,
+    concurrency: usize,
+    progress: bool,
+    fetch_tree: F,
+) -> Result<Vec<TreeNode>, Box<dyn Error>>
+where
+    F: Fn(String) -> Fut,
+    Fut: std::future::Future<Output = Result<GitTree, String>> + Send + 'static,
+{
+    use std::io::IsTerminal;
+    let progress = progress && std::io::stderr().is_terminal();
+    let mut nodes = Vec::new();
+    let mut pending = std::collections::VecDeque::from([(String::new(), root_url.to_string())]);
+    let mut in_flight = tokio::task::JoinSet::new();
+    let mut walked = 0;
+
+    loop {
+        while in_flight.len() < concurrency.max(1) {
+            let Some((prefix, url)) = pending.pop_front() else {
+                break;
+            };
+            let listing = fetch_tree(url);
+            in_flight.spawn(async move { Ok::<_, String>((prefix, listing.await?)) });
+        }
+
+        let Some(joined) = in_flight.join_next().await else {
+            break;
+        };
+        let (prefix, tree) = joined??;
+        walked += 1;
+
+        for mut node in tree.tree {
+            if !prefix.is_empty() {
+                node.path = format!("{}/{}", prefix, node.path);
+            }
+            if node.r#type == "tree" {
+                if let Some(url) = &node.url {
+                    pending.push_back((node.path.clone(), url.clone()));
+                }
+            }
+            nodes.push(node);
+        }
+
+        if progress {
+            eprint!(
+                "\rWalking tree: {} directories listed, {} queued, {} entries",
+                walked,
+                pending.len() + in_flight.len(),
+                nodes.len()
+            );
+        }
+    }
+    if progress {
+        // Clear the counter so the report starts on a clean line
+        eprint!("\r\x1b[2K");
+    }
+    info!("Walked tree: {} directories listed, {} entries", walked, nodes.len());
+
+    nodes.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(nodes)
+}
+
+/// Keeps only the archive entries that are blobs in the (filtered) tree.
+fn archive_files(mut archive: HashMap<String, Vec<u8>>, nodes: &[TreeNode]) -> FetchedFiles {
+    let mut files = HashMap::new();
+    let mut skipped = Vec::new();
+    for node in nodes.iter().filter(|node| node.is_file()) {
+        match archive.remove_entry(&node.path) {
+            Some((path, content)) => {
+                files.insert(path, content);
+            }
+            None => skipped.push(SkipRecord::new(&node.path, "missing from archive")),
+        }
+    }
+    (files, skipped)
+}
+
+// --strip-prefix only changes how paths read; detection already ran on the full paths
+fn display_nodes<'a>(nodes: &'a [TreeNode], options: &Options) -> Cow<'a, [TreeNode]> {
+    match &options.strip_prefix {
+        Some(prefix) => Cow::Owned(strip_tree_prefix(nodes, prefix)),
+        None => Cow::Borrowed(nodes),
+    }
+}
+
+// The tree is text-only; --no-tree always hides it, --tree brings it back for --stats-only
+fn wants_tree(options: &Options) -> bool {
+    options.format == OutputFormat::Text
+        && !options.tui
+        && !options.no_tree
+        && (options.tree || !options.stats_only)
+}
+
+fn show_tree(nodes: &[TreeNode], options: &Options) {
+    let nodes = display_nodes(nodes, options);
+    match options.tree_format {
+        TreeFormat::Indented => crate::display::print_tree(&nodes, 0),
+        TreeFormat::Flat => crate::display::print_flat_tree(&nodes),
+    }
+}
+
+fn display_view<'a>(analysis: &'a RepoAnalysis, options: &Options) -> Cow<'a, RepoAnalysis> {
+    match &options.strip_prefix {
+        Some(prefix) => {
+            let mut analysis = analysis.clone();
+            analysis.strip_path_prefix(prefix);
+            Cow::Owned(analysis)
+        }
+        None => Cow::Borrowed(analysis),
+    }
+}
+
+// `name` identifies the repository on the `compact` line
+fn write_machine_output(name: &str, analysis: &RepoAnalysis, options: &Options) -> Result<(), Box<dyn Error>> {
+    let analysis = &*display_view(analysis, options);
+    let mut writer = crate::export::output_writer(options.output.as_deref())?;
+    match options.format {
+        OutputFormat::Csv => crate::export::write_csv(analysis, writer),
+        OutputFormat::Ndjson => {
+            for record in &analysis.files {
+                crate::export::write_ndjson_line(&mut writer, record)?;
+            }
+            crate::export::write_ndjson_summary(&mut writer, analysis, options.examples)
+        }
+        OutputFormat::Json => crate::export::write_json(analysis, writer, options.summary, options.examples),
+        OutputFormat::Sarif => crate::export::write_sarif(analysis, writer),
+        OutputFormat::Compact => Ok(crate::export::write_compact(name, analysis, writer)?),
+        OutputFormat::Text => Ok(()),
+    }
+}
+
+// Emits one line per file while fetching, then a summary line once the analysis is done
+async fn stream_ndjson(
+    client: &GitHubClient,
+    nodes: &[TreeNode],
+    mappings: &FileMappings,
+    sampled_from: Option<usize>,
+    options: &Options,
+) -> Result<HashMap<String, Vec<u8>>, Box<dyn Error>> {
+    let mut writer = crate::export::output_writer(options.output.as_deref())?;
+    let mut write_error: Option<std::io::Error> = None;
+    let shas = blob_shas(nodes);
+
+    let (files, skipped) = fetch_files_streaming(client, nodes, &mut |path, bytes| {
+        if write_error.is_none() {
+            let mut record = file_record(path, bytes, mappings);
+            if options.include_content_hash {
+                record.sha = shas.get(path).map(|sha| sha.to_string());
+            }
+            if let Some(prefix) = &options.strip_prefix {
+                record.path = strip_path_prefix(path, prefix).to_string();
+            }
+            if let Err(e) = crate::export::write_ndjson_line(&mut writer, &record) {
+                write_error = Some(e);
+            }
+        }
+    })
+    .await?;
+
+    if let Some(e) = write_error {
+        return Err(e.into());
+    }
+
+    let mut analysis = analyze_repo(nodes, &files, mappings, &build_registry(options)).await;
+    analysis.record_skips(skipped);
+    analysis.partial = client.deadline_reached();
+    analysis.sampled_from = sampled_from;
+    report_skips(&analysis, options);
+    crate::export::write_ndjson_summary(&mut writer, &analysis, options.examples)?;
+    Ok(files)
+}
+
+#[derive(Deserialize, Debug)]
+struct Comparison {
+    total_commits: u64,
+    #[serde(default)]
+    files: Vec<ChangedFile>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ChangedFile {
+    filename: String,
+    status: String,
+    sha: Option<String>,
+}
+
+// Present changed files as tree nodes so the regular fetch/analysis path applies
+fn changed_nodes(files: &[ChangedFile], owner: &str, repo: &str, filter: &PathFilter) -> Vec<TreeNode> {
+    files
+        .iter()
+        .filter(|file| file.status != "removed")
+        .filter(|file| filter.matches(&file.filename))
+        .filter_map(|file| {
+            let sha = file.sha.clone()?;
+            Some(TreeNode {
+                path: file.filename.clone(),
+                mode: "100644".to_string(),
+                r#type: "blob".to_string(),
+                url: Some(format!(
+                    "https://api.github.com/repos/{}/{}/git/blobs/{}",
+                    owner, repo, sha
+                )),
+                sha,
+                size: None,
+            })
+        })
+        .collect()
+}
+
+const PR_FILES_PAGE_SIZE: usize = 100;
+
+// GitHub lists at most 3000 files for a pull request
+async fn fetch_pull_request_files(
+    client: &GitHubClient,
+    owner: &str,
+    repo: &str,
+    number: u64,
+) -> Result<Vec<ChangedFile>, Box<dyn Error>> {
+    let mut files = Vec::new();
+
+    for page in 1.. {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/pulls/{}/files?per_page={}&page={}",
+            owner, repo, number, PR_FILES_PAGE_SIZE, page
+        );
+        let res = client.get_with_retry(&url).await?;
+        if !res.status().is_success() {
+            return Err(format!(
+                "Failed to list files of pull request #{}: {} - {}",
+                number,
+                res.status(),
+                res.text().await?
+            )
+            .into());
+        }
+
+        let batch: Vec<ChangedFile> = parse_json(res).await?;
+        let done = batch.len() < PR_FILES_PAGE_SIZE;
+        files.extend(batch);
+        if done {
+            break;
+        }
+    }
+
+    Ok(files)
+}
+
+// "80% TypeScript, 20% JSON" over the changed files that were analyzed
+fn change_composition(analysis: &RepoAnalysis) -> String {
+    let counts = analysis.type_counts();
+    let total: usize = counts.values().sum();
+    let mut counts: Vec<(&str, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+
+    counts
+        .iter()
+        .map(|(file_type, count)| format!("{:.0}% {}", *count as f64 * 100.0 / total as f64, file_type))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Runs detection on just the files a pull request touches and summarises the change.
+async fn fetch_and_display_pull_request(
+    client: &GitHubClient,
+    owner: &str,
+    repo: &str,
+    number: u64,
+    mappings: &FileMappings,
+    options: &Options,
+) -> Result<(), Box<dyn Error>> {
+    let filter = PathFilter::from_options(options)?;
+    let changed = fetch_pull_request_files(client, owner, repo, number).await?;
+    let count_status = |status: &str| changed.iter().filter(|f| f.status == status).count();
+
+    let nodes = changed_nodes(&changed, owner, repo, &filter);
+    let (files, skipped) = fetch_files(client, &nodes).await?;
+    let mut analysis = analyze_repo(&nodes, &files, mappings, &build_registry(options)).await;
+    analysis.record_skips(skipped);
+    if options.include_content_hash {
+        analysis.attach_blob_shas(&nodes);
+    }
+    analysis.partial = client.deadline_reached();
+
+    match options.format {
+        OutputFormat::Text => {
+            println!(
+                "Pull request #{}: {} files changed (added {}, modified {}, removed {}, renamed {})",
+                number,
+                changed.len(),
+                count_status("added"),
+                count_status("modified"),
+                count_status("removed"),
+                count_status("renamed")
+            );
+            if !analysis.files.is_empty() {
+                println!("Composition: {}", change_composition(&analysis));
+            }
+
+            let new_configs = changed
+                .iter()
+                .filter(|f| f.status == "added")
+                .filter(|f| analysis.files.iter().any(|r| r.path == f.filename && r.category == "config_files"))
+                .count();
+            if new_configs > 0 {
+                println!("Adds {} new config file{}", new_configs, if new_configs == 1 { "" } else { "s" });
+            }
+            display_analysis(&analysis, options);
+        }
+        _ => write_machine_output(&format!("{}/{}#{}", owner, repo, number), &analysis, options)?,
+    }
+
+    Ok(())
+}
+
+// The compare API lists at most 300 changed files per comparison
+async fn fetch_and_display_changes(
+    client: &GitHubClient,
+    owner: &str,
+    repo: &str,
+    base: &str,
+    head: &str,
+    mappings: &FileMappings,
+    options: &Options,
+) -> Result<(), Box<dyn Error>> {
+    let filter = PathFilter::from_options(options)?;
+    let compare_url = format!(
+        "https://api.github.com/repos/{}/{}/compare/{}...{}",
+        owner, repo, base, head
+    );
+    let compare_res = client.get(&compare_url).send().await?;
+
+    if !compare_res.status().is_success() {
+        return Err(format!(
+            "Failed to compare {}...{}: {} - {}",
+            base,
+            head,
+            compare_res.status(),
+            compare_res.text().await?
+        )
+        .into());
+    }
+
+    let comparison: Comparison = parse_json(compare_res).await?;
+    let count_status = |status: &str| comparison.files.iter().filter(|f| f.status == status).count();
+
+    let changed_nodes = changed_nodes(&comparison.files, owner, repo, &filter);
+
+    let (files, skipped) = fetch_files(client, &changed_nodes).await?;
+    let mut analysis = analyze_repo(&changed_nodes, &files, mappings, &build_registry(options)).await;
+    analysis.record_skips(skipped);
+    if options.include_content_hash {
+        analysis.attach_blob_shas(&changed_nodes);
+    }
+    analysis.partial = client.deadline_reached();
+
+    match options.format {
+        OutputFormat::Text => {
+            println!(
+                "Changes since {}: {} files in {} commits (added {}, modified {}, removed {}, renamed {})",
+                base,
+                comparison.files.len(),
+                comparison.total_commits,
+                count_status("added"),
+                count_status("modified"),
+                count_status("removed"),
+                count_status("renamed")
+            );
+            display_analysis(&analysis, options);
+        }
+        _ => write_machine_output(&format!("{}/{}@{}...{}", owner, repo, base, head), &analysis, options)?,
+    }
+
+    Ok(())
+}
+
+#[derive(Deserialize, Debug)]
+struct Gist {
+    files: HashMap<String, GistFile>,
+}
+
+#[derive(Deserialize, Debug)]
+struct GistFile {
+    content: Option<String>,
+    raw_url: Option<String>,
+    #[serde(default)]
+    truncated: bool,
+}
+
+// Accepts https://gist.github.com/<user>/<id> as well as https://gist.github.com/<id>
+fn extract_gist_id(url: &str) -> Option<String> {
+    let rest = url
+        .strip_prefix("https://gist.github.com/")
+        .or_else(|| url.strip_prefix("http://gist.github.com/"))?;
+    let id = rest.trim_end_matches('/').rsplit('/').next()?;
+    let id = id.trim_end_matches(".git");
+    if id.is_empty() {
+        None
+    } else {
+        Some(id.to_string())
+    }
+}
+
+async fn fetch_gist_files(client: &GitHubClient, gist_id: &str) -> Result<FetchedFiles, Box<dyn Error>> {
+    let gist_url = format!("https://api.github.com/gists/{}", gist_id);
+    let gist_res = client.get(&gist_url).send().await?;
+
+    if !gist_res.status().is_success() {
+        return Err(format!(
+            "Failed to fetch gist: {} - {}",
+            gist_res.status(),
+            gist_res.text().await?
+        )
+        .into());
+    }
+
+    let gist: Gist = parse_json(gist_res).await?;
+    let mut files = HashMap::new();
+    let mut skipped = Vec::new();
+
+    for (name, file) in gist.files {
+        // Large gist files are truncated inline and have to be fetched from raw_url
+        let content = match (file.truncated, file.content, file.raw_url) {
+            (false, Some(content), _) => content.into_bytes(),
+            (_, _, Some(raw_url)) => {
+                let res = client.get(&raw_url).send().await?;
+                if !res.status().is_success() {
+                    // An error page (403, 404, rate limit) must not be analyzed as the file
+                    warn!("Skipping gist file {}: {}", name, res.status());
+                    skipped.push(SkipRecord::new(&name, format!("fetch failed: {}", res.status())));
+                    continue;
+                }
+                res.bytes().await?.to_vec()
+            }
+            (_, Some(content), None) => content.into_bytes(),
+            (_, None, None) => {
+                warn!("Skipping gist file {} due to missing content.", name);
+                skipped.push(SkipRecord::new(&name, "no download URL"));
+                continue;
+            }
+        };
+        files.insert(name, content);
+    }
+
+    Ok((files, skipped))
+}
+
+async fn fetch_and_display_gist(gist_id: &str, options: &Options) -> Result<(), Box<dyn Error>> {
+    let client = GitHubClient::new(&ClientConfig::from_options(options))?;
+    let mappings = load_file_mappings()?;
+
+    let filter = PathFilter::from_options(options)?;
+    let (mut files, mut skipped) = fetch_gist_files(&client, gist_id).await?;
+    files.retain(|path, _| filter.matches(path));
+    skipped.retain(|skip| filter.matches(&skip.path));
+    let mut analysis = analyze_repo(&[], &files, &mappings, &build_registry(options)).await;
+    analysis.record_skips(skipped);
+
+    match options.format {
+        OutputFormat::Text => {
+            println!("Gist: {} ({} files)", gist_id, files.len());
+            display_analysis(&analysis, options);
+        }
+        _ => write_machine_output(&format!("gist:{}", gist_id), &analysis, options)?,
+    }
+    report_skips(&analysis, options);
+
+    Ok(())
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct RateLimitResponse {
+    pub(crate) resources: RateLimitResources,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct RateLimitResources {
+    pub(crate) core: RateLimit,
+    search: RateLimit,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RateLimit {
+    pub limit: u64,
+    pub remaining: u64,
+    pub used: u64,
+    pub reset: u64,
+}
+
+fn display_rate_limit(resource: &str, rate_limit: &RateLimit) {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let reset_in = rate_limit.reset.saturating_sub(now);
+
+    println!(
+        "{:<8}{}/{} remaining, resets at {} (in {}m {}s)",
+        format!("{}:", resource),
+        rate_limit.remaining,
+        rate_limit.limit,
+        rate_limit.reset,
+        reset_in / 60,
+        reset_in % 60
+    );
+}
+
+pub(crate) async fn fetch_rate_limit(client: &GitHubClient) -> Result<RateLimitResponse, Box<dyn Error>> {
+    let res = client.get("https://api.github.com/rate_limit").send().await?;
+
+    if !res.status().is_success() {
+        return Err(format!(
+            "Failed to fetch rate limit: {} - {}",
+            res.status(),
+            res.text().await?
+        )
+        .into());
+    }
+
+    Ok(parse_json(res).await?)
+}
+
+pub async fn show_rate_limit(options: &Options) -> Result<(), Box<dyn Error>> {
+    let client = GitHubClient::new(&ClientConfig::from_options(options))?;
+    let rate_limit = fetch_rate_limit(&client).await?;
+    println!(
+        "Rate limit ({}):",
+        if client.has_token() { "authenticated" } else { "unauthenticated" }
+    );
+    display_rate_limit("core", &rate_limit.resources.core);
+    display_rate_limit("search", &rate_limit.resources.search);
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct Capabilities<'a> {
+    version: &'static str,
+    formats: Vec<String>,
+    detectors: Vec<&'static str>,
+    project_types: Vec<&'static str>,
+    frameworks: &'static [&'static str],
+    file_types: IndexMap<&'static str, Vec<&'a str>>,
+}
+
+/// Prints, as JSON, what this build can detect and emit, for tools wrapping it.
+pub fn show_capabilities() -> Result<(), Box<dyn Error>> {
+    let mappings = load_file_mappings()?;
+    // Optional detectors are included: they are capabilities even when off by default
+    let registry = build_registry(&Options {
+        scan_secrets: true,
+        list_deps: true,
+        check_licenses: true,
+        ..Options::default()
+    });
+
+    let capabilities = Capabilities {
+        version: env!("CARGO_PKG_VERSION"),
+        formats: OutputFormat::value_variants()
+            .iter()
+            .filter_map(|format| format.to_possible_value())
+            .map(|value| value.get_name().to_string())
+            .collect(),
+        detectors: registry.names(),
+        project_types: ProjectType::known_labels(),
+        frameworks: crate::detectors::known_frameworks(),
+        file_types: mappings
+            .file_types()
+            .categories()
+            .into_iter()
+            .map(|(category, types)| (category, types.keys().map(String::as_str).collect()))
+            .collect(),
+    };
+    println!("{}", serde_json::to_string_pretty(&capabilities)?);
+    Ok(())
+}
+
+/// Reports how many requests a real run would make for `nodes` and whether the
+/// remaining core quota covers them, without downloading any content.
+async fn report_dry_run(
+    client: &GitHubClient,
+    nodes: &[TreeNode],
+    options: &Options,
+) -> Result<(), Box<dyn Error>> {
+    let blobs = nodes.iter().filter(|node| node.is_file()).count() as u64;
+    let strategy = options.resolved_fetch_strategy();
+    // Raw downloads skip the REST API quota, but each is still a request to GitHub
+    // and counts towards its secondary (abuse) limits
+    let (requests, raw_requests) = match strategy {
+        FetchStrategy::Archive => (1, 0),
+        FetchStrategy::Raw => (0, blobs),
+        FetchStrategy::Blob | FetchStrategy::Contents => (blobs, 0),
+    };
+
+    println!("Dry run: {} files would be analyzed", blobs);
+    match strategy {
+        FetchStrategy::Archive => println!("Estimated requests: 1 (archive download)"),
+        FetchStrategy::Raw => println!("Estimated requests: ~{} raw requests, 0 API requests", raw_requests),
+        FetchStrategy::Contents => println!("Estimated requests: ~{} contents requests", requests),
+        FetchStrategy::Blob => println!("Estimated requests: ~{} blob requests", requests),
+    }
+    if raw_requests > 0 {
+        println!("Raw requests are outside the API quota but still count towards secondary rate limits");
+    }
+
+    let core = fetch_rate_limit(client).await?.resources.core;
+    if requests <= core.remaining {
+        println!("Fits within the remaining rate limit ({}/{})", core.remaining, core.limit);
+    } else {
+        println!(
+            "Exceeds the remaining rate limit ({}/{}) by {} requests",
+            core.remaining,
+            core.limit,
+            requests - core.remaining
+        );
+    }
+
+    Ok(())
+}
+
+#[derive(Debug)]
+struct Submodule {
+    path: String,
+    url: String,
+}
+
+fn parse_gitmodules(content: &str) -> Vec<Submodule> {
+    let mut submodules = Vec::new();
+    let mut path = None;
+    let mut url = None;
+
+    for line in content.lines().map(str::trim) {
+        if line.starts_with("[submodule") {
+            if let (Some(path), Some(url)) = (path.take(), url.take()) {
+                submodules.push(Submodule { path, url });
+            }
+            continue;
+        }
+
+        if let Some((key, value)) = line.split_once('=') {
+            match key.trim() {
+                "path" => path = Some(value.trim().to_string()),
+                "url" => url = Some(value.trim().to_string()),
+                _ => {}
+            }
+        }
+    }
+
+    if let (Some(path), Some(url)) = (path, url) {
+        submodules.push(Submodule { path, url });
+    }
+
+    submodules
+}
+
+// Normalizes the SSH and `.git` forms used in .gitmodules to a GitHub web URL
+fn submodule_github_url(url: &str) -> Option<String> {
+    let url = url.trim_end_matches('/').trim_end_matches(".git");
+    let repo_path = url
+        .strip_prefix("git@github.com:")
+        .or_else(|| url.strip_prefix("https://github.com/"))
+        .or_else(|| url.strip_prefix("http://github.com/"))
+        .or_else(|| url.strip_prefix("ssh://git@github.com/"))?;
+
+    Some(format!("https://github.com/{}", repo_path))
+}
+
+async fn analyze_submodules(files: &HashMap<String, Vec<u8>>, options: &Options) {
+    let Some(gitmodules) = files.get(".gitmodules") else {
+        println!("No .gitmodules file found; skipping submodule analysis.");
+        return;
+    };
+
+    // Only follow one level of submodules to avoid unbounded recursion
+    let mut nested_options = options.clone();
+    nested_options.include_submodules = false;
+
+    for submodule in parse_gitmodules(&String::from_utf8_lossy(gitmodules)) {
+        let Some(url) = submodule_github_url(&submodule.url) else {
+            warn!(
+                "Skipping submodule {}: unsupported URL {}",
+                submodule.path, submodule.url
+            );
+            continue;
+        };
+
+        println!("==================================================");
+        println!("Submodule: {} ({})", submodule.path, url);
+        if let Err(e) = Box::pin(fetch_and_display_tree(&url, &nested_options)).await {
+            error!("Failed to analyze submodule {}: {}", submodule.path, e);
+        }
+    }
+}
+
+pub(crate) fn extract_owner_repo(repo_url: &str) -> Result<(Host, String, String), Box<dyn Error>> {
+    let url_parts: Vec<&str> = repo_url.trim_end_matches('/').split('/').collect();
+    if url_parts.len() < 5 {
+        return Err(format!("Invalid repository URL: {}", repo_url).into());
+    }
+    let host = Host::from_domain(url_parts[2])
+        .ok_or_else(|| format!("Unsupported host: {}", url_parts[2]))?;
+
+    let (owner, repo) = match host {
+        // GitLab projects can sit in nested groups; `/-/` starts the non-project part
+        Host::GitLab => {
+            let path: Vec<&str> = url_parts[3..].iter().take_while(|p| **p != "-").copied().collect();
+            match path.split_last() {
+                Some((project, namespace)) if !namespace.is_empty() => (namespace.join("/"), project.to_string()),
+                _ => return Err(format!("Invalid GitLab URL: {}", repo_url).into()),
+            }
+        }
+        _ => (url_parts[3].to_string(), url_parts[4].to_string()),
+    };
+    Ok((host, owner, repo.trim_end_matches(".git").to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gitmodules_entries_need_a_path_and_url() {
+        let content = "[submodule \"vendor/lib\"]\n\tpath = vendor/lib\n\turl = git@github.com:owner/lib.git\n[submodule \"broken\"]\n\tpath = broken\n[submodule \"docs\"]\n\tpath = docs\n\turl = https://github.com/owner/docs\n";
+        let submodules: Vec<(String, String)> = parse_gitmodules(content)
+            .into_iter()
+            .map(|submodule| (submodule.path, submodule.url))
+            .collect();
+        assert_eq!(
+            submodules,
+            [
+                ("vendor/lib".to_string(), "git@github.com:owner/lib.git".to_string()),
+                ("docs".to_string(), "https://github.com/owner/docs".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn submodule_urls_are_normalized_to_github_web_urls() {
+        assert_eq!(
+            submodule_github_url("git@github.com:owner/lib.git").as_deref(),
+            Some("https://github.com/owner/lib")
+        );
+        assert_eq!(
+            submodule_github_url("ssh://git@github.com/owner/lib/").as_deref(),
+            Some("https://github.com/owner/lib")
+        );
+        assert_eq!(submodule_github_url("https://gitlab.com/owner/lib.git"), None);
+    }
+
+    #[test]
+    fn gist_ids_are_taken_from_either_url_form() {
+        assert_eq!(extract_gist_id("https://gist.github.com/octocat/aa5a315d61ae9438b18d").as_deref(), Some("aa5a315d61ae9438b18d"));
+        assert_eq!(extract_gist_id("https://gist.github.com/aa5a315d61ae9438b18d/").as_deref(), Some("aa5a315d61ae9438b18d"));
+        assert_eq!(extract_gist_id("https://gist.github.com/octocat/aa5a315d61ae9438b18d.git").as_deref(), Some("aa5a315d61ae9438b18d"));
+        assert_eq!(extract_gist_id("https://github.com/octocat/repo"), None);
+    }
+
+    #[test]
+    fn sloc_skips_blank_lines_and_comments() {
+        let rust = "// header\n\nfn main() {\n    /* block\n       still comment */\n    println!(\"hi\");\n}\n";
+        assert_eq!(count_sloc("Rust", rust), 3);
+
+        let python = "# comment\nimport os\n\nprint(os.name)\n";
+        assert_eq!(count_sloc("Python", python), 2);
+    }
+
+    #[test]
+    fn unknown_types_only_drop_blank_lines() {
+        assert_eq!(count_sloc("Unknown", "# kept\n\n// kept\n"), 2);
+    }
+
+    // The generic pattern is listed first each time, so only specificity can pick the other
+    const PRECEDENCE_MAPPINGS: &str = r#"{
+        "file_types": {
+            "programming_languages": {
+                "TypeScript": ["*.ts"],
+                "TypeScript Declarations": ["*.d.ts"],
+                "JavaScript": ["*.js"],
+                "Minified JavaScript": ["*.min.js"]
+            },
+            "web_files": {},
+            "config_files": {
+                "Docker": ["Dockerfile"],
+                "Docker Variant": ["Dockerfile.*"]
+            },
+            "documentation": {},
+            "images": {},
+            "video": {},
+            "audio": {},
+            "archives": {},
+            "fonts": {},
+            "other": {}
+        }
+    }"#;
+
+    fn classify(path: &str) -> String {
+        let mut mappings: FileMappings = serde_json::from_str(PRECEDENCE_MAPPINGS).unwrap();
+        mappings.index = FileTypeIndex::build(&mappings.file_types);
+        assert_eq!(
+            mappings.classify(path),
+            mappings.classify_linear(path),
+            "index and linear scan disagree on {}",
+            path
+        );
+        detect_file_type(path, &mappings).0
+    }
+
+    #[test]
+    fn compound_suffix_beats_plain_extension() {
+        assert_eq!(classify("src/types/index.d.ts"), "TypeScript Declarations");
+        assert_eq!(classify("src/index.ts"), "TypeScript");
+        assert_eq!(classify("dist/app.min.js"), "Minified JavaScript");
+        assert_eq!(classify("src/app.js"), "JavaScript");
+    }
+
+    #[test]
+    fn exact_name_and_glob_match_their_own_files() {
+        assert_eq!(classify("Dockerfile"), "Docker");
+        assert_eq!(classify("docker/Dockerfile.dev"), "Docker Variant");
+        assert_eq!(classify("Dockerfile2"), "Unknown");
+    }
+
+    #[test]
+    fn generated_files_are_known_suffixes_or_header_markers() {
+        assert!(is_generated("api/user.pb.go", "package api\n"));
+        // Minified bundles are counted separately
+        assert!(!is_generated("dist/app.min.js", ""));
+        assert!(is_generated("src/schema.rs", "// @generated by diesel\nuse x;\n"));
+        assert!(!is_generated("src/main.rs", "fn main() {}\n"));
+        // Markers only count in the header
+        assert!(!is_generated("src/lib.rs", "a\nb\nc\nd\ne\n// DO NOT EDIT\n"));
+    }
+
+    #[test]
+    fn minified_files_are_named_so_or_have_very_long_lines() {
+        let bundle = format!("var a={};", "1,".repeat(600));
+        assert!(is_minified("dist/app.js", "JavaScript", &bundle, 1));
+        assert!(is_minified("dist/app.min.css", "CSS", "a{}", 1));
+
+        let readable = "function add(a, b) {\n    return a + b;\n}\n".repeat(40);
+        assert!(!is_minified("src/add.js", "JavaScript", &readable, 120));
+        // Short one-liners and other types are never flagged
+        assert!(!is_minified("src/tiny.js", "JavaScript", "export default 1;", 1));
+        assert!(!is_minified("data/blob.json", "JSON", &bundle, 1));
+    }
+
+    #[test]
+    fn shebangs_name_the_script_type() {
+        let cases = [
+            ("#!/usr/bin/env python3\nprint('hi')", Some("Python")),
+            ("#!/usr/bin/python3.11", Some("Python")),
+            ("#!/bin/bash\nset -e", Some("Shell")),
+            ("#!/bin/sh", Some("Shell")),
+            ("#!/usr/bin/env -S node --no-warnings", Some("JavaScript")),
+            ("#!/usr/bin/env ruby", Some("Ruby")),
+            ("#!/usr/bin/env -S deno run --allow-net", Some("TypeScript")),
+            ("#!/usr/bin/env LANG=C perl -w", Some("Perl")),
+            ("#Requires -Version 7\nWrite-Host hi", Some("PowerShell")),
+            ("#!/usr/bin/env unknown-runtime", None),
+            ("echo no shebang", None),
+            ("", None),
+        ];
+        for (content, expected) in cases {
+            assert_eq!(sniff_script_type(content), expected, "{:?}", content);
+        }
+    }
+
+    #[test]
+    fn extensionless_scripts_are_classified_by_shebang() {
+        let mappings = load_file_mappings().unwrap();
+        let record = file_record("bin/tool", b"#!/usr/bin/env python3\n", &mappings);
+        assert_eq!((record.file_type.as_str(), record.category.as_str()), ("Python", "programming_languages"));
+        // A known extension is never overridden by the first line
+        assert_eq!(file_record("notes.md", b"#!/bin/bash\n", &mappings).file_type, "Markdown");
+    }
+
+    fn blob(path: &str, mode: &str) -> TreeNode {
+        TreeNode {
+            path: path.to_string(),
+            mode: mode.to_string(),
+            r#type: "blob".to_string(),
+            sha: String::new(),
+            size: None,
+            url: None,
+        }
+    }
+
+    #[test]
+    fn depth_counts_files_but_not_symlinks() {
+        let tree = [
+            blob("README.md", "100644"),
+            blob("src/bin/cli.rs", "100755"),
+            blob("docs/a/b/c/d/latest", TreeNode::SYMLINK_MODE),
+        ];
+        assert_eq!(DepthStats::from_tree(&tree), DepthStats { max: 3, average: 2.0 });
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn deadline_keeps_the_files_fetched_before_it() {
+        let tree: Vec<TreeNode> = ["a", "b", "c", "d"].iter().map(|path| blob(path, "100644")).collect();
+        let deadline = tokio::time::Instant::now() + Duration::from_millis(2500);
+
+        // Each fetch takes a second of the paused clock, so two complete in time
+        let (files, skipped) = fetch_until(
+            &tree,
+            Some(deadline),
+            |node| async move {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                Ok(Some(node.path.clone().into_bytes()))
+            },
+            &mut |_, _| {},
+        )
+        .await;
+
+        let mut fetched: Vec<&str> = files.keys().map(String::as_str).collect();
+        fetched.sort();
+        assert_eq!(fetched, ["a", "b"]);
+        let skipped: Vec<(&str, &str)> = skipped.iter().map(|s| (s.path.as_str(), s.reason.as_str())).collect();
+        assert_eq!(skipped, [("c", "deadline reached"), ("d", "deadline reached")]);
+        // The fetch in flight is cut off at the deadline rather than allowed to finish
+        assert_eq!(tokio::time::Instant::now(), deadline);
+    }
+
+    // A chain of 1000 nested directories, each holding ten files and the next directory
+    #[tokio::test]
+    async fn walk_tree_lists_a_deep_tree() {
+        const DEPTH: usize = 1000;
+        const FILES_PER_DIR: usize = 10;
+
+        let nodes = walk_tree_with("0", 8, false, |url| async move {
+            let level: usize = url.parse().unwrap();
+            let node = |path: String, r#type: &str, url: Option<String>| TreeNode {
+                path,
+                mode: "100644".to_string(),
+                r#type: r#type.to_string(),
+                sha: String::new(),
+                size: Some(1),
+                url,
+            };
+            let mut tree: Vec<TreeNode> = (0..FILES_PER_DIR)
+                .map(|i| node(format!("f{}.rs", i), "blob", None))
+                .collect();
+            if level + 1 < DEPTH {
+                tree.push(node("d".to_string(), "tree", Some((level + 1).to_string())));
+            }
+            Ok(GitTree {
+                sha: String::new(),
+                url,
+                tree,
+                truncated: false,
+            })
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(nodes.len(), DEPTH * FILES_PER_DIR + DEPTH - 1);
+        let deepest = format!("{}f9.rs", "d/".repeat(DEPTH - 1));
+        assert!(nodes.iter().any(|node| node.path == deepest));
+    }
+}
@@ -0,0 +1,57 @@
+use flate2::read::GzDecoder;
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::{self, Read};
+use tar::{Archive, EntryType};
+use tracing::info;
+
+use crate::client::GitHubClient;
+
+/// Downloads `GET /repos/{owner}/{repo}/tarball/{ref}` and returns every regular
+/// file in it, keyed by its path relative to the repository root.
+pub async fn fetch_archive(
+    client: &GitHubClient,
+    owner: &str,
+    repo: &str,
+    reference: &str,
+) -> Result<HashMap<String, Vec<u8>>, Box<dyn Error>> {
+    let url = format!(
+        "https://api.github.com/repos/{}/{}/tarball/{}",
+        owner, repo, reference
+    );
+    info!("Downloading archive: {}", url);
+
+    let response = client.get_with_retry(&url).await?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to download archive: {}", response.status()).into());
+    }
+
+    let body = response.bytes().await?;
+    Ok(unpack_tarball(&body[..])?)
+}
+
+/// Reads a gzipped tarball as GitHub produces it: every entry sits under a single
+/// `{owner}-{repo}-{sha}/` directory, which is stripped from the returned paths.
+pub fn unpack_tarball<R: Read>(reader: R) -> io::Result<HashMap<String, Vec<u8>>> {
+    let mut archive = Archive::new(GzDecoder::new(reader));
+    let mut files = HashMap::new();
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if entry.header().entry_type() != EntryType::Regular {
+            continue;
+        }
+
+        let path = entry.path()?.to_string_lossy().into_owned();
+        let relative = match path.split_once('/') {
+            Some((_, rest)) if !rest.is_empty() => rest.to_string(),
+            _ => continue,
+        };
+
+        let mut content = Vec::with_capacity(entry.size() as usize);
+        entry.read_to_end(&mut content)?;
+        files.insert(relative, content);
+    }
+
+    Ok(files)
+}
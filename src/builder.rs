@@ -0,0 +1,144 @@
+use std::error::Error;
+
+use crate::api::{analyze_forge, extract_owner_repo, fetch_and_display_tree, RepoAnalysis};
+use crate::cli::{Options, OutputFormat};
+use crate::client::{ClientConfig, GitHubClient};
+use crate::forge::{BitbucketForge, GitHubForge, GitLabForge, Host};
+
+/// Fluent entry point for using the crate as a library:
+///
+/// ```no_run
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// use project_type_checker::AnalysisBuilder;
+///
+/// let analysis = AnalysisBuilder::new("https://github.com/owner/repo")
+///     .token("ghp_...")
+///     .branch("develop")
+///     .exclude("vendor/**")
+///     .concurrency(8)
+///     .run()
+///     .await?;
+/// println!("{}", analysis.project_type);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct AnalysisBuilder {
+    url: String,
+    options: Options,
+}
+
+impl AnalysisBuilder {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            options: Options::default(),
+        }
+    }
+
+    /// Starts from an existing set of options, e.g. parsed command-line flags.
+    pub fn with_options(url: impl Into<String>, options: Options) -> Self {
+        Self {
+            url: url.into(),
+            options,
+        }
+    }
+
+    pub fn token(mut self, token: impl Into<String>) -> Self {
+        self.options.token = Some(token.into());
+        self
+    }
+
+    pub fn proxy(mut self, proxy: impl Into<String>) -> Self {
+        self.options.proxy = Some(proxy.into());
+        self
+    }
+
+    pub fn branch(mut self, branch: impl Into<String>) -> Self {
+        self.options.branch = Some(branch.into());
+        self
+    }
+
+    /// Adds an include glob; may be called repeatedly.
+    pub fn include(mut self, glob: impl Into<String>) -> Self {
+        self.options.include.push(glob.into());
+        self
+    }
+
+    /// Adds an exclude glob; may be called repeatedly.
+    pub fn exclude(mut self, glob: impl Into<String>) -> Self {
+        self.options.exclude.push(glob.into());
+        self
+    }
+
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.options.concurrency = concurrency;
+        self
+    }
+
+    /// Output format used by [`AnalysisBuilder::display`].
+    pub fn format(mut self, format: OutputFormat) -> Self {
+        self.options.format = format;
+        self
+    }
+
+    pub fn deadline(mut self, seconds: u64) -> Self {
+        self.options.deadline = Some(seconds);
+        self
+    }
+
+    pub fn scan_secrets(mut self, enabled: bool) -> Self {
+        self.options.scan_secrets = enabled;
+        self
+    }
+
+    pub fn list_deps(mut self, enabled: bool) -> Self {
+        self.options.list_deps = enabled;
+        self
+    }
+
+    pub fn options(&self) -> &Options {
+        &self.options
+    }
+
+    /// Fetches and analyzes the repository, returning the results without printing.
+    pub async fn run(self) -> Result<RepoAnalysis, Box<dyn Error>> {
+        let (host, owner, repo) = extract_owner_repo(&self.url)?;
+        let mut config = ClientConfig::from_options(&self.options);
+        if host != Host::GitHub {
+            config.token = None;
+        }
+        let client = GitHubClient::new(&config)?;
+
+        let (_, analysis) = match host {
+            Host::GitHub => {
+                let forge = GitHubForge::new(&client, &owner, &repo).with_concurrency(self.options.concurrency);
+                analyze_forge(&forge, &self.options).await?
+            }
+            Host::GitLab => analyze_forge(&GitLabForge::new(&client, &owner, &repo), &self.options).await?,
+            Host::Bitbucket => analyze_forge(&BitbucketForge::new(&client, &owner, &repo), &self.options).await?,
+        };
+        Ok(analysis)
+    }
+
+    /// Runs the same pipeline as the command line, printing in the configured format.
+    pub async fn display(self) -> Result<(), Box<dyn Error>> {
+        fetch_and_display_tree(&self.url, &self.options).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_builders_start_from_the_command_line_defaults() {
+        let builder = AnalysisBuilder::new("https://github.com/owner/repo");
+        assert_eq!(builder.options().concurrency, 8);
+        assert_eq!(builder.options().format, OutputFormat::Text);
+
+        let builder = builder.concurrency(2).include("src/**");
+        assert_eq!(builder.options().concurrency, 2);
+        assert_eq!(builder.options().include, ["src/**"]);
+    }
+}
@@ -0,0 +1,42 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// On-disk cache of git blob contents. A blob SHA names its content, so a cached blob
+/// is valid for every ref and repository it appears in and never needs invalidating.
+#[derive(Debug, Clone)]
+pub struct BlobCache {
+    dir: PathBuf,
+}
+
+impl BlobCache {
+    pub fn new(cache_dir: &Path) -> Self {
+        Self {
+            dir: cache_dir.join("blobs"),
+        }
+    }
+
+    // Fanned out as `ab/cdef…` like `.git/objects`, so no directory grows too large
+    fn path_for(&self, sha: &str) -> Option<PathBuf> {
+        let valid = sha.len() > 2 && sha.bytes().all(|b| b.is_ascii_hexdigit());
+        valid.then(|| self.dir.join(&sha[..2]).join(&sha[2..]))
+    }
+
+    pub fn get(&self, sha: &str) -> Option<Vec<u8>> {
+        fs::read(self.path_for(sha)?).ok()
+    }
+
+    pub fn put(&self, sha: &str, content: &[u8]) -> io::Result<()> {
+        let Some(path) = self.path_for(sha) else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // Written aside and renamed, so an interrupted run never leaves a truncated blob
+        let partial = path.with_extension(format!("partial-{}", std::process::id()));
+        fs::write(&partial, content)?;
+        fs::rename(&partial, &path)
+    }
+}
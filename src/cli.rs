@@ -0,0 +1,269 @@
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "project_type_checker",
+    about = "Analyze the file types and project type of a GitHub repository",
+    // Presets are expanded ahead of the user's flags, so the last occurrence must win
+    args_override_self = true
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Repository URL (e.g. https://github.com/owner/repo) or local directory; prompts interactively when omitted
+    pub url: Option<String>,
+
+    #[command(flatten)]
+    pub options: Options,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Show the remaining GitHub API quota for the configured token
+    Ratelimit,
+    /// Print the supported formats, detectors, project types, frameworks and file types as JSON
+    Capabilities,
+    /// Check the mappings, API connectivity, the token and the rate limit
+    Doctor,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct Options {
+    /// GitHub token used to authenticate API requests
+    #[arg(long, env = "GITHUB_TOKEN", hide_env_values = true, global = true)]
+    pub token: Option<String>,
+
+    /// Proxy URL for all requests; HTTP_PROXY/HTTPS_PROXY/NO_PROXY are honored otherwise
+    #[arg(long, value_name = "URL", global = true)]
+    pub proxy: Option<String>,
+
+    /// Log verbosity (error, warn, info, debug, trace); overrides RUST_LOG
+    #[arg(long, value_name = "LEVEL", global = true)]
+    pub log_level: Option<String>,
+
+    /// Apply the flags of this preset from projectchecker.toml; explicit flags take precedence
+    #[arg(long, value_name = "NAME")]
+    pub preset: Option<String>,
+
+    /// Analyze every repository of this GitHub organization and print an org-wide summary
+    #[arg(long, value_name = "NAME")]
+    pub org: Option<String>,
+
+    /// Analyze this branch, tag or commit (full or short SHA) instead of the default branch
+    #[arg(long, value_name = "NAME")]
+    pub branch: Option<String>,
+
+    /// Analyze a published release (`latest` or a tag name) instead of a branch
+    #[arg(long, value_name = "TAG", conflicts_with = "branch")]
+    pub release: Option<String>,
+
+    /// Trim this leading directory from displayed paths, e.g. when analyzing packages/web
+    #[arg(long, value_name = "PATH")]
+    pub strip_prefix: Option<String>,
+
+    /// If the branch's tree is not found, retry with main, master, then the default branch
+    #[arg(long)]
+    pub branch_fallback: bool,
+
+    /// Output format for the analysis results
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+
+    /// Add each file's git blob SHA to the per-file csv/json/ndjson records
+    #[arg(long)]
+    pub include_content_hash: bool,
+
+    /// With --format json, emit only the summary and omit the per-file records
+    #[arg(long)]
+    pub summary: bool,
+
+    /// Show one example path (the largest file) per detected file type
+    #[arg(long)]
+    pub examples: bool,
+
+    /// Add up related file types under the labels of the mappings' `groups` section
+    #[arg(long)]
+    pub group: bool,
+
+    /// How the tree is printed; `flat` lists sorted file paths for diffing or scripting
+    #[arg(long, value_enum, default_value_t = TreeFormat::Indented)]
+    pub tree_format: TreeFormat,
+
+    /// Show which paths led to each detected project type, framework and tool
+    #[arg(long)]
+    pub explain: bool,
+
+    /// List the Unknown files grouped by extension, with counts and an example path
+    #[arg(long)]
+    pub explain_unknown: bool,
+
+    /// Print the tree even with --stats-only
+    #[arg(long, overrides_with = "no_tree")]
+    pub tree: bool,
+
+    /// Skip the tree and print only the analysis
+    #[arg(long, overrides_with = "tree")]
+    pub no_tree: bool,
+
+    /// Only print the file-type breakdown and project type, without the tree
+    #[arg(long)]
+    pub stats_only: bool,
+
+    /// Write machine-readable output to this file instead of stdout
+    #[arg(long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// Only analyze files matching this glob (repeatable)
+    #[arg(long, value_name = "GLOB")]
+    pub include: Vec<String>,
+
+    /// Skip files matching this glob (repeatable); applied after --include
+    #[arg(long, value_name = "GLOB")]
+    pub exclude: Vec<String>,
+
+    /// Only analyze files changed between this commit and the default branch
+    #[arg(long, value_name = "SHA")]
+    pub since: Option<String>,
+
+    /// Only analyze the files changed in this pull request
+    #[arg(long, value_name = "NUMBER", conflicts_with = "since")]
+    pub pr: Option<u64>,
+
+    /// Analyze the repository's wiki (cloned with git) instead of its code
+    #[arg(long)]
+    pub wiki: bool,
+
+    /// Scan fetched file contents for likely secrets (AWS keys, private keys, GitHub tokens)
+    #[arg(long)]
+    pub scan_secrets: bool,
+
+    /// List dependencies declared in Cargo.toml, package.json, requirements.txt,
+    /// pyproject.toml, go.mod and pom.xml
+    #[arg(long)]
+    pub list_deps: bool,
+
+    /// Flag GPL-licensed vendored components inside a permissively licensed repository
+    #[arg(long)]
+    pub check_licenses: bool,
+
+    /// Resolve submodules from .gitmodules and analyze them too (one level deep)
+    #[arg(long)]
+    pub include_submodules: bool,
+
+    /// Download the repository tarball in one request instead of fetching each blob
+    #[arg(long)]
+    pub archive: bool,
+
+    /// Where file contents are downloaded from; each endpoint has its own quota and latency
+    #[arg(long, value_enum, default_value_t = FetchStrategy::Blob)]
+    pub fetch_strategy: FetchStrategy,
+
+    /// Fetch only the tree and estimate the API requests a full run would make
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Keep downloaded file contents here, keyed by blob SHA, and reuse them on later runs
+    #[arg(long, value_name = "PATH", env = "PROJECTCHECKER_CACHE_DIR")]
+    pub cache_dir: Option<PathBuf>,
+
+    /// Only fetch and analyze N randomly chosen files, for a quick estimate of a large repo
+    #[arg(long, value_name = "N")]
+    pub sample: Option<usize>,
+
+    /// Seed for --sample, so the same files are chosen on every run
+    #[arg(long, value_name = "SEED", requires = "sample")]
+    pub seed: Option<u64>,
+
+    /// Stop fetching after this many seconds and report partial results
+    #[arg(long, value_name = "SECS")]
+    pub deadline: Option<u64>,
+
+    /// Re-analyze every SECS seconds and print how the composition changed
+    #[arg(long, value_name = "SECS")]
+    pub watch: Option<u64>,
+
+    /// Print how long the tree fetch, content fetch and analysis took (to stderr)
+    #[arg(long)]
+    pub timings: bool,
+
+    /// List every file that was not analyzed and why (to stderr)
+    #[arg(long)]
+    pub debug_skips: bool,
+
+    /// Browse the tree and per-directory stats in an interactive terminal UI
+    #[arg(long)]
+    pub tui: bool,
+
+    /// When analyzing a local directory, save a PROJECTCHECKER.md summary into it
+    #[arg(long)]
+    pub write_report: bool,
+
+    /// Overwrite an existing PROJECTCHECKER.md with --write-report
+    #[arg(long, requires = "write_report")]
+    pub force: bool,
+
+    /// Maximum concurrent requests when walking a tree GitHub returned truncated
+    #[arg(long, value_name = "N", default_value_t = 8)]
+    pub concurrency: usize,
+}
+
+// Same values as a bare command-line run (environment-backed flags included), so
+// library callers never see zeroes where clap would have filled in a default
+impl Default for Options {
+    fn default() -> Self {
+        Cli::parse_from(["project_type_checker"]).options
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human-readable report
+    #[default]
+    Text,
+    /// One `path,type,category,size,lines,sloc,generated,minified` row per file
+    Csv,
+    /// One JSON document with per-file records and a summary
+    Json,
+    /// One JSON object per file as it is classified, then a summary line
+    Ndjson,
+    /// SARIF 2.1.0 log of secret-scan and quality findings, for code-scanning tools
+    Sarif,
+    /// One summary line per repository, for appending to a log while scanning many
+    Compact,
+}
+
+impl Options {
+    /// `--fetch-strategy`, with `--archive` as shorthand for `archive`
+    pub fn resolved_fetch_strategy(&self) -> FetchStrategy {
+        if self.archive {
+            FetchStrategy::Archive
+        } else {
+            self.fetch_strategy
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FetchStrategy {
+    /// One git blob API request per file
+    #[default]
+    Blob,
+    /// One Contents API request per file, by path at the analyzed ref; files over 1 MB fail
+    Contents,
+    /// Download each file from raw.githubusercontent.com, outside the REST API quota
+    Raw,
+    /// The whole repository as one tarball
+    Archive,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TreeFormat {
+    /// Directories and files, indented by depth
+    #[default]
+    Indented,
+    /// One file path per line, sorted, without directories
+    Flat,
+}
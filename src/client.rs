@@ -0,0 +1,125 @@
+use reqwest::header::{HeaderMap, AUTHORIZATION, RETRY_AFTER, USER_AGENT};
+use reqwest::{NoProxy, Proxy, RequestBuilder, Response, StatusCode};
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::warn;
+
+use crate::cache::BlobCache;
+use crate::cli::Options;
+
+/// How many times a request is retried after a secondary rate limit response.
+pub(crate) const MAX_SECONDARY_RETRIES: u32 = 3;
+
+/// Returns the wait GitHub asks for when it trips its secondary (abuse) rate limit.
+/// Those responses are 403/429 with `Retry-After`; an exhausted primary quota is a
+/// 403 without it and is not retried here.
+pub fn secondary_retry_after(status: StatusCode, headers: &HeaderMap) -> Option<Duration> {
+    if status != StatusCode::FORBIDDEN && status != StatusCode::TOO_MANY_REQUESTS {
+        return None;
+    }
+
+    headers
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+/// Connection settings shared by every client the tool builds.
+#[derive(Debug, Clone, Default)]
+pub struct ClientConfig {
+    pub token: Option<String>,
+    /// Explicit proxy URL; without it reqwest falls back to HTTP(S)_PROXY/NO_PROXY
+    pub proxy: Option<String>,
+    /// Total time budget for the run, counted from when the client is built
+    pub deadline: Option<Duration>,
+    /// Directory for the blob content cache; blobs are always downloaded when unset
+    pub cache_dir: Option<PathBuf>,
+}
+
+impl ClientConfig {
+    pub fn from_options(options: &Options) -> Self {
+        Self {
+            token: options.token.clone(),
+            proxy: options.proxy.clone(),
+            deadline: options.deadline.map(Duration::from_secs),
+            cache_dir: options.cache_dir.clone(),
+        }
+    }
+}
+
+/// Thin wrapper around `reqwest::Client` that attaches the headers every GitHub
+/// request needs (user agent and, when configured, the auth token).
+#[derive(Clone)]
+pub struct GitHubClient {
+    http: reqwest::Client,
+    token: Option<String>,
+    deadline: Option<Instant>,
+    blob_cache: Option<BlobCache>,
+}
+
+impl GitHubClient {
+    pub fn new(config: &ClientConfig) -> reqwest::Result<Self> {
+        let mut builder = reqwest::Client::builder();
+        if let Some(proxy) = &config.proxy {
+            builder = builder.proxy(Proxy::all(proxy)?.no_proxy(NoProxy::from_env()));
+        }
+
+        Ok(Self {
+            http: builder.build()?,
+            token: config.token.clone().filter(|t| !t.is_empty()),
+            deadline: config.deadline.map(|budget| Instant::now() + budget),
+            blob_cache: config.cache_dir.as_deref().map(BlobCache::new),
+        })
+    }
+
+    pub fn has_token(&self) -> bool {
+        self.token.is_some()
+    }
+
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    pub fn blob_cache(&self) -> Option<&BlobCache> {
+        self.blob_cache.as_ref()
+    }
+
+    pub fn deadline_reached(&self) -> bool {
+        self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Sends a GET, sleeping and retrying when GitHub signals a secondary rate limit.
+    pub async fn get_with_retry(&self, url: &str) -> reqwest::Result<Response> {
+        let mut attempt = 0;
+        loop {
+            let response = self.get(url).send().await?;
+            match secondary_retry_after(response.status(), response.headers()) {
+                Some(wait) if attempt < MAX_SECONDARY_RETRIES => {
+                    attempt += 1;
+                    warn!(
+                        "Secondary rate limit hit for {}; retrying in {}s (attempt {}/{})",
+                        url,
+                        wait.as_secs(),
+                        attempt,
+                        MAX_SECONDARY_RETRIES
+                    );
+                    tokio::time::sleep(wait).await;
+                }
+                _ => return Ok(response),
+            }
+        }
+    }
+
+    pub fn get(&self, url: &str) -> RequestBuilder {
+        let request = self.http.get(url).header(USER_AGENT, "rust-tool");
+        match &self.token {
+            Some(token) => request.header(AUTHORIZATION, format!("Bearer {}", token)),
+            None => request,
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use clap::Parser;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::error::Error;
+use std::ffi::OsString;
+use std::fs;
+use std::path::Path;
+
+use crate::cli::Cli;
+
+/// Looked up in the working directory, like `extensions.json`.
+pub const CONFIG_FILE: &str = "projectchecker.toml";
+
+/// Contents of `projectchecker.toml`. A preset maps long option names to values:
+///
+/// ```toml
+/// [presets.audit]
+/// scan-secrets = true
+/// list-deps = true
+/// format = "json"
+/// exclude = ["vendor/**", "*.min.js"]
+/// ```
+#[derive(Deserialize, Debug, Default)]
+pub struct Config {
+    #[serde(default)]
+    pub presets: HashMap<String, toml::Table>,
+}
+
+pub fn load_config(path: &Path) -> Result<Config, Box<dyn Error>> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
+    toml::from_str(&content).map_err(|e| format!("Failed to parse '{}': {}", path.display(), e).into())
+}
+
+/// Turns a preset into command-line flags: `true` becomes `--key`, `false` is
+/// dropped, arrays repeat the flag and any other value becomes `--key value`.
+fn preset_args(name: &str, preset: &toml::Table) -> Result<Vec<OsString>, Box<dyn Error>> {
+    let mut args = Vec::new();
+
+    for (key, value) in preset {
+        let flag = OsString::from(format!("--{}", key));
+        match value {
+            toml::Value::Boolean(true) => args.push(flag),
+            toml::Value::Boolean(false) => {}
+            toml::Value::Array(values) => {
+                for value in values {
+                    args.push(flag.clone());
+                    args.push(preset_value(name, key, value)?);
+                }
+            }
+            value => {
+                args.push(flag);
+                args.push(preset_value(name, key, value)?);
+            }
+        }
+    }
+
+    Ok(args)
+}
+
+fn preset_value(name: &str, key: &str, value: &toml::Value) -> Result<OsString, Box<dyn Error>> {
+    match value {
+        toml::Value::String(s) => Ok(s.into()),
+        toml::Value::Integer(i) => Ok(i.to_string().into()),
+        toml::Value::Float(f) => Ok(f.to_string().into()),
+        _ => Err(format!("Preset '{}': unsupported value for '{}'", name, key).into()),
+    }
+}
+
+/// Expands `--preset NAME` into the preset's flags. They are inserted right after
+/// the program name, so any flag the user passes explicitly overrides them.
+pub fn expand_presets(args: Vec<OsString>) -> Result<Vec<OsString>, Box<dyn Error>> {
+    // Malformed command lines are left for the real parse to report
+    let Some(name) = Cli::try_parse_from(&args).ok().and_then(|cli| cli.options.preset) else {
+        return Ok(args);
+    };
+
+    let config = load_config(Path::new(CONFIG_FILE))?;
+    let preset = config
+        .presets
+        .get(&name)
+        .ok_or_else(|| format!("Preset '{}' is not defined in {}", name, CONFIG_FILE))?;
+
+    let mut expanded = args;
+    let rest = expanded.split_off(1.min(expanded.len()));
+    expanded.extend(preset_args(&name, preset)?);
+    expanded.extend(rest);
+    Ok(expanded)
+}
@@ -0,0 +1,465 @@
+use regex::Regex;
+use std::collections::HashMap;
+
+use crate::api::TreeNode;
+use crate::detectors::{Detector, Finding, FindingKind};
+
+/// A dependency declared in a manifest; `version` is the requirement as written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dependency {
+    pub ecosystem: &'static str,
+    pub name: String,
+    pub version: String,
+}
+
+type ManifestParser = fn(&str) -> Vec<(String, String)>;
+
+// Manifest file name, ecosystem, parser
+const MANIFESTS: &[(&str, &str, ManifestParser)] = &[
+    ("Cargo.toml", "Rust", parse_cargo_toml),
+    ("package.json", "Node", parse_package_json),
+    ("requirements.txt", "Python", parse_requirements_txt),
+    ("pyproject.toml", "Python", parse_pyproject_toml),
+    ("go.mod", "Go", parse_go_mod),
+    ("pom.xml", "Java", parse_pom_xml),
+];
+
+fn toml_version(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(version) => version.clone(),
+        toml::Value::Table(table) => table
+            .get("version")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .or_else(|| table.get("git").map(|_| "git".to_string()))
+            .or_else(|| table.get("path").map(|_| "path".to_string()))
+            .unwrap_or_else(|| "*".to_string()),
+        _ => "*".to_string(),
+    }
+}
+
+pub fn parse_cargo_toml(content: &str) -> Vec<(String, String)> {
+    let Ok(manifest) = content.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+
+    ["dependencies", "dev-dependencies", "build-dependencies"]
+        .iter()
+        .filter_map(|section| manifest.get(*section)?.as_table())
+        .flatten()
+        .map(|(name, value)| (name.clone(), toml_version(value)))
+        .collect()
+}
+
+pub fn parse_package_json(content: &str) -> Vec<(String, String)> {
+    let Ok(manifest) = serde_json::from_str::<serde_json::Value>(content) else {
+        return Vec::new();
+    };
+
+    ["dependencies", "devDependencies", "peerDependencies", "optionalDependencies"]
+        .iter()
+        .filter_map(|section| manifest.get(*section)?.as_object())
+        .flatten()
+        .map(|(name, version)| (name.clone(), version.as_str().unwrap_or("*").to_string()))
+        .collect()
+}
+
+// Splits a PEP 508 requirement such as `requests[socks]>=2.31; python_version>"3.8"`
+fn parse_requirement(requirement: &str) -> Option<(String, String)> {
+    let requirement = requirement.split(';').next()?.trim();
+    let end = requirement
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .unwrap_or(requirement.len());
+    let name = &requirement[..end];
+    if name.is_empty() {
+        return None;
+    }
+
+    let rest = requirement[end..].trim_start();
+    let rest = match rest.strip_prefix('[') {
+        Some(extras) => extras.split_once(']').map_or("", |(_, after)| after).trim(),
+        None => rest,
+    };
+    let version = if rest.is_empty() { "*" } else { rest };
+    Some((name.to_string(), version.to_string()))
+}
+
+pub fn parse_requirements_txt(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('-'))
+        .filter_map(parse_requirement)
+        .collect()
+}
+
+pub fn parse_pyproject_toml(content: &str) -> Vec<(String, String)> {
+    let Ok(manifest) = content.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    let mut deps = Vec::new();
+
+    // PEP 621
+    if let Some(list) = manifest
+        .get("project")
+        .and_then(|p| p.get("dependencies"))
+        .and_then(|d| d.as_array())
+    {
+        deps.extend(list.iter().filter_map(|d| parse_requirement(d.as_str()?)));
+    }
+
+    // Poetry
+    if let Some(table) = manifest
+        .get("tool")
+        .and_then(|t| t.get("poetry"))
+        .and_then(|p| p.get("dependencies"))
+        .and_then(|d| d.as_table())
+    {
+        deps.extend(
+            table
+                .iter()
+                .filter(|(name, _)| name.as_str() != "python")
+                .map(|(name, value)| (name.clone(), toml_version(value))),
+        );
+    }
+
+    deps
+}
+
+pub fn parse_go_mod(content: &str) -> Vec<(String, String)> {
+    let mut deps = Vec::new();
+    let mut in_block = false;
+
+    for line in content.lines().map(|l| l.split("//").next().unwrap_or("").trim()) {
+        let spec = if in_block {
+            if line == ")" {
+                in_block = false;
+                continue;
+            }
+            line
+        } else if line == "require (" {
+            in_block = true;
+            continue;
+        } else if let Some(spec) = line.strip_prefix("require ") {
+            spec
+        } else {
+            continue;
+        };
+
+        let mut parts = spec.split_whitespace();
+        if let (Some(module), Some(version)) = (parts.next(), parts.next()) {
+            deps.push((module.to_string(), version.to_string()));
+        }
+    }
+
+    deps
+}
+
+pub fn parse_pom_xml(content: &str) -> Vec<(String, String)> {
+    let dependency = Regex::new(r"(?s)<dependency>(.*?)</dependency>").expect("invalid dependency pattern");
+    let tag = |block: &str, name: &str| {
+        Regex::new(&format!(r"<{0}>\s*([^<]*?)\s*</{0}>", name))
+            .expect("invalid tag pattern")
+            .captures(block)
+            .map(|c| c[1].to_string())
+    };
+
+    dependency
+        .captures_iter(content)
+        .filter_map(|block| {
+            let block = &block[1];
+            let group = tag(block, "groupId")?;
+            let artifact = tag(block, "artifactId")?;
+            let version = tag(block, "version").unwrap_or_else(|| "*".to_string());
+            Some((format!("{}:{}", group, artifact), version))
+        })
+        .collect()
+}
+
+/// Every dependency declared in a recognised manifest, deduplicated per ecosystem
+/// and name and sorted. Differing requirements for one name are joined.
+pub fn extract_dependencies(files: &HashMap<String, Vec<u8>>) -> Vec<Dependency> {
+    let mut deps: Vec<Dependency> = Vec::new();
+
+    let mut paths: Vec<&String> = files.keys().collect();
+    paths.sort();
+
+    for path in paths {
+        let file_name = path.rsplit('/').next().unwrap_or(path);
+        let Some((_, ecosystem, parser)) = MANIFESTS.iter().find(|(name, _, _)| *name == file_name) else {
+            continue;
+        };
+
+        for (name, version) in parser(&String::from_utf8_lossy(&files[path])) {
+            match deps.iter_mut().find(|d| d.ecosystem == *ecosystem && d.name == name) {
+                Some(existing) => {
+                    if !existing.version.split(", ").any(|v| v == version) {
+                        existing.version = format!("{}, {}", existing.version, version);
+                    }
+                }
+                None => deps.push(Dependency {
+                    ecosystem,
+                    name,
+                    version,
+                }),
+            }
+        }
+    }
+
+    deps.sort_by(|a, b| (a.ecosystem, &a.name).cmp(&(b.ecosystem, &b.name)));
+    deps
+}
+
+type RuntimeParser = fn(&str) -> Vec<(String, String)>;
+
+// Manifest file name, parser returning (runtime, version requirement) pairs
+const RUNTIME_MANIFESTS: &[(&str, RuntimeParser)] = &[
+    ("Cargo.toml", cargo_rust_version),
+    ("package.json", package_engines),
+    ("pyproject.toml", pyproject_requires_python),
+    ("setup.py", setup_python_requires),
+    ("setup.cfg", setup_python_requires),
+    ("go.mod", go_directive),
+];
+
+// `rust-version` is a minimum, so it is shown as one
+fn cargo_rust_version(content: &str) -> Vec<(String, String)> {
+    let Ok(manifest) = content.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    let package = manifest.get("package").or_else(|| manifest.get("workspace")?.get("package"));
+
+    package
+        .and_then(|p| p.get("rust-version")?.as_str())
+        .map(|version| vec![("Rust".to_string(), format!(">={}", version))])
+        .unwrap_or_default()
+}
+
+fn package_engines(content: &str) -> Vec<(String, String)> {
+    let Ok(manifest) = serde_json::from_str::<serde_json::Value>(content) else {
+        return Vec::new();
+    };
+    let Some(engines) = manifest.get("engines").and_then(|e| e.as_object()) else {
+        return Vec::new();
+    };
+
+    engines
+        .iter()
+        .filter_map(|(engine, version)| {
+            let runtime = match engine.as_str() {
+                "node" => "Node",
+                other => other,
+            };
+            Some((runtime.to_string(), version.as_str()?.to_string()))
+        })
+        .collect()
+}
+
+fn pyproject_requires_python(content: &str) -> Vec<(String, String)> {
+    let Ok(manifest) = content.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    // PEP 621 first, then Poetry's `python` pseudo-dependency
+    let pep621 = manifest.get("project").and_then(|p| p.get("requires-python")?.as_str());
+    let poetry = || {
+        manifest
+            .get("tool")?
+            .get("poetry")?
+            .get("dependencies")?
+            .get("python")?
+            .as_str()
+    };
+
+    pep621
+        .or_else(poetry)
+        .map(|version| vec![("Python".to_string(), version.to_string())])
+        .unwrap_or_default()
+}
+
+// Covers both `python_requires=">=3.8"` in setup.py and `python_requires = >=3.8` in setup.cfg
+fn setup_python_requires(content: &str) -> Vec<(String, String)> {
+    let pattern = Regex::new(r#"python_requires\s*=\s*(?:["']([^"']+)["']|([^\r\n"'#]+))"#)
+        .expect("invalid python_requires pattern");
+    pattern
+        .captures(content)
+        .and_then(|c| c.get(1).or(c.get(2)))
+        .map(|version| vec![("Python".to_string(), version.as_str().trim().to_string())])
+        .unwrap_or_default()
+}
+
+// The `go` directive is the minimum Go version for the module
+fn go_directive(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("go "))
+        .map(|version| vec![("Go".to_string(), format!(">={}", version.trim()))])
+        .unwrap_or_default()
+}
+
+/// Runtime versions required by recognised manifests, as (runtime, requirement,
+/// manifest path). Identical requirements from several manifests are reported once.
+pub fn extract_runtime_versions(files: &HashMap<String, Vec<u8>>) -> Vec<(String, String, String)> {
+    let mut versions: Vec<(String, String, String)> = Vec::new();
+
+    let mut paths: Vec<&String> = files.keys().collect();
+    // Shallowest manifests first, so the root one is the path reported
+    paths.sort_by_key(|path| (path.matches('/').count(), path.as_str()));
+
+    for path in paths {
+        let file_name = path.rsplit('/').next().unwrap_or(path);
+        let Some((_, parser)) = RUNTIME_MANIFESTS.iter().find(|(name, _)| *name == file_name) else {
+            continue;
+        };
+
+        for (runtime, version) in parser(&String::from_utf8_lossy(&files[path])) {
+            if !versions.iter().any(|(r, v, _)| *r == runtime && *v == version) {
+                versions.push((runtime, version, path.clone()));
+            }
+        }
+    }
+
+    versions
+}
+
+pub struct RuntimeVersionDetector;
+
+impl Detector for RuntimeVersionDetector {
+    fn name(&self) -> &'static str {
+        "runtime-versions"
+    }
+
+    // Label is the runtime; details are [version requirement, manifest path]
+    fn detect(&self, _tree: &[TreeNode], files: &HashMap<String, Vec<u8>>) -> Vec<Finding> {
+        extract_runtime_versions(files)
+            .into_iter()
+            .map(|(runtime, version, path)| {
+                Finding::new(FindingKind::RuntimeVersion, runtime)
+                    .with_details(vec![version, path.clone()])
+                    .with_evidence(vec![path])
+            })
+            .collect()
+    }
+}
+
+pub struct DependencyDetector;
+
+impl Detector for DependencyDetector {
+    fn name(&self) -> &'static str {
+        "dependencies"
+    }
+
+    // Label is the package name; details are [ecosystem, version requirement]
+    fn detect(&self, _tree: &[TreeNode], files: &HashMap<String, Vec<u8>>) -> Vec<Finding> {
+        extract_dependencies(files)
+            .into_iter()
+            .map(|dep| {
+                Finding::new(FindingKind::Dependency, dep.name)
+                    .with_details(vec![dep.ecosystem.to_string(), dep.version])
+            })
+            .collect()
+    }
+}
+
+pub fn display_dependencies(findings: &[&Finding]) {
+    println!("Dependencies: {}", findings.len());
+    let mut ecosystem = None;
+    for finding in findings {
+        let current = finding.details.first().map(String::as_str);
+        if current != ecosystem {
+            println!("  {}:", current.unwrap_or("Other"));
+            ecosystem = current;
+        }
+        println!("    {} {}", finding.label, finding.details.get(1).map_or("*", String::as_str));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requirements_keep_the_specifier_and_drop_extras_and_markers() {
+        let content = "# pinned\nrequests[socks]>=2.31; python_version > \"3.8\"\nflask\n-r dev.txt\n";
+        assert_eq!(
+            parse_requirements_txt(content),
+            [
+                ("requests".to_string(), ">=2.31".to_string()),
+                ("flask".to_string(), "*".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn go_mod_reads_single_and_block_requires() {
+        let content = "module example.com/app\n\nrequire github.com/a/b v1.2.0\n\nrequire (\n\tgolang.org/x/net v0.20.0 // indirect\n)\n";
+        assert_eq!(
+            parse_go_mod(content),
+            [
+                ("github.com/a/b".to_string(), "v1.2.0".to_string()),
+                ("golang.org/x/net".to_string(), "v0.20.0".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn dependencies_are_merged_per_ecosystem_and_sorted() {
+        let files: HashMap<String, Vec<u8>> = [
+            ("Cargo.toml", "[dependencies]\nserde = \"1\"\n\n[dev-dependencies]\ntokio = { version = \"1.35\" }\n"),
+            ("crates/cli/Cargo.toml", "[dependencies]\nserde = \"1.0.190\"\nlocal = { path = \"../local\" }\n"),
+            ("pom.xml", "<dependency><groupId>org.slf4j</groupId><artifactId>slf4j-api</artifactId></dependency>"),
+        ]
+        .into_iter()
+        .map(|(path, content)| (path.to_string(), content.as_bytes().to_vec()))
+        .collect();
+
+        let deps = extract_dependencies(&files);
+        let found: Vec<(&str, &str, &str)> =
+            deps.iter().map(|d| (d.ecosystem, d.name.as_str(), d.version.as_str())).collect();
+        assert_eq!(
+            found,
+            [
+                ("Java", "org.slf4j:slf4j-api", "*"),
+                ("Rust", "local", "path"),
+                ("Rust", "serde", "1, 1.0.190"),
+                ("Rust", "tokio", "1.35"),
+            ]
+        );
+    }
+
+    #[test]
+    fn setup_files_quoted_and_unquoted_python_requires() {
+        assert_eq!(
+            setup_python_requires("setup(\n    python_requires=\">=3.8\",\n)\n"),
+            [("Python".to_string(), ">=3.8".to_string())]
+        );
+        assert_eq!(
+            setup_python_requires("[options]\npython_requires = >=3.9\n"),
+            [("Python".to_string(), ">=3.9".to_string())]
+        );
+    }
+
+    #[test]
+    fn runtime_versions_report_the_shallowest_manifest_once() {
+        let files: HashMap<String, Vec<u8>> = [
+            ("Cargo.toml", "[package]\nname = \"app\"\nrust-version = \"1.70\"\n"),
+            ("crates/core/Cargo.toml", "[package]\nname = \"core\"\nrust-version = \"1.70\"\n"),
+            ("web/package.json", r#"{"engines": {"node": ">=18"}}"#),
+            ("go.mod", "module example.com/app\n\ngo 1.21\n"),
+        ]
+        .into_iter()
+        .map(|(path, content)| (path.to_string(), content.as_bytes().to_vec()))
+        .collect();
+
+        let versions = extract_runtime_versions(&files);
+        let found: Vec<(&str, &str, &str)> =
+            versions.iter().map(|(r, v, p)| (r.as_str(), v.as_str(), p.as_str())).collect();
+        assert_eq!(
+            found,
+            [
+                ("Rust", ">=1.70", "Cargo.toml"),
+                ("Go", ">=1.21", "go.mod"),
+                ("Node", ">=18", "web/package.json"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,1663 @@
+use indexmap::IndexMap;
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::LazyLock;
+
+use crate::api::TreeNode;
+use crate::project_type::ProjectType;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FindingKind {
+    ProjectType,
+    Readme,
+    EntryPoint,
+    Configuration,
+    Submodule,
+    Secret,
+    Lockfile,
+    Task,
+    DevEnvironment,
+    ApiSurface,
+    TargetPlatform,
+    Dependency,
+    RuntimeVersion,
+    Framework,
+    DocsTool,
+    CoverageTool,
+    Changelog,
+    I18n,
+    Locale,
+    License,
+    LicenseConflict,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub kind: FindingKind,
+    /// Short verdict, e.g. "Website" or the path that triggered the finding
+    pub label: String,
+    /// Supporting values, e.g. the keys a config template declares
+    pub details: Vec<String>,
+    /// Why the finding was reported: the triggering paths, shown by `--explain`
+    pub evidence: Vec<String>,
+}
+
+impl Finding {
+    pub fn new(kind: FindingKind, label: impl Into<String>) -> Self {
+        Self {
+            kind,
+            label: label.into(),
+            details: Vec::new(),
+            evidence: Vec::new(),
+        }
+    }
+
+    pub fn with_details(mut self, details: Vec<String>) -> Self {
+        self.details = details;
+        self
+    }
+
+    pub fn with_evidence(mut self, evidence: Vec<String>) -> Self {
+        self.evidence = evidence;
+        self
+    }
+}
+
+/// A single piece of classification logic run over the fetched tree and contents.
+pub trait Detector: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn detect(&self, tree: &[TreeNode], files: &HashMap<String, Vec<u8>>) -> Vec<Finding>;
+}
+
+#[derive(Default)]
+pub struct DetectorRegistry {
+    detectors: Vec<Box<dyn Detector>>,
+}
+
+impl DetectorRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry preloaded with all built-in detectors
+    pub fn with_defaults() -> Self {
+        let mut registry = Self::new();
+        registry.register(Box::new(ProjectTypeDetector));
+        registry.register(Box::new(ReadmeDetector));
+        registry.register(Box::new(EntryPointDetector));
+        registry.register(Box::new(ConfigTemplateDetector));
+        registry.register(Box::new(SubmoduleDetector));
+        registry.register(Box::new(LockfileDetector));
+        registry.register(Box::new(TaskDetector));
+        registry.register(Box::new(MlProjectDetector));
+        registry.register(Box::new(BrowserExtensionDetector));
+        registry.register(Box::new(DevEnvironmentDetector));
+        registry.register(Box::new(GrpcDetector));
+        registry.register(Box::new(TargetPlatformDetector));
+        registry.register(Box::new(StaticSiteDetector));
+        registry.register(Box::new(DocsToolDetector));
+        registry.register(Box::new(CoverageToolDetector));
+        registry.register(Box::new(ChangelogDetector));
+        registry.register(Box::new(I18nDetector));
+        registry.register(Box::new(crate::deps::RuntimeVersionDetector));
+        registry.register(Box::new(crate::licenses::RootLicenseDetector));
+        registry
+    }
+
+    pub fn register(&mut self, detector: Box<dyn Detector>) {
+        self.detectors.push(detector);
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.detectors.iter().map(|d| d.name()).collect()
+    }
+
+    pub fn run(&self, tree: &[TreeNode], files: &HashMap<String, Vec<u8>>) -> Vec<Finding> {
+        self.detectors
+            .iter()
+            .flat_map(|detector| detector.detect(tree, files))
+            .collect()
+    }
+}
+
+/// Frameworks in precedence order: when a repo shows indicators for several,
+/// the earliest entry wins (e.g. Next.js over React, Nuxt over Vue.js).
+const FRAMEWORK_PRECEDENCE: &[&str] = &["Next.js", "Nuxt", "Angular", "Vue.js", "React"];
+
+const JS_CONFIG_EXTENSIONS: &[&str] = &["js", "mjs", "cjs", "ts", "mts", "cts"];
+
+/// Config file stems that identify a framework, with the extensions each may take
+/// (e.g. `next.config.*` covers `.js`, `.mjs`, `.ts`, ...).
+const FRAMEWORK_FILES: &[(&str, &[&str], &str)] = &[
+    ("next.config", JS_CONFIG_EXTENSIONS, "Next.js"),
+    ("nuxt.config", JS_CONFIG_EXTENSIONS, "Nuxt"),
+    ("angular", &["json"], "Angular"),
+    ("vue.config", JS_CONFIG_EXTENSIONS, "Vue.js"),
+];
+
+/// Dependency names in package.json that identify a framework.
+const FRAMEWORK_PACKAGES: &[(&str, &str)] = &[
+    ("next", "Next.js"),
+    ("nuxt", "Nuxt"),
+    ("@angular/core", "Angular"),
+    ("vue", "Vue.js"),
+    ("react", "React"),
+];
+
+/// Version requirement of `framework` as written in a package.json dependency
+/// section, preferring the top-most manifest that declares it.
+fn framework_version(framework: &str, files: &HashMap<String, Vec<u8>>) -> Option<String> {
+    let (package, _) = FRAMEWORK_PACKAGES.iter().find(|(_, f)| *f == framework)?;
+
+    let mut manifests: Vec<&String> = files
+        .keys()
+        .filter(|path| path.rsplit('/').next() == Some("package.json"))
+        .collect();
+    manifests.sort_by_key(|path| (path.matches('/').count(), path.as_str()));
+
+    manifests.into_iter().find_map(|path| {
+        crate::deps::parse_package_json(&String::from_utf8_lossy(&files[path]))
+            .into_iter()
+            .find(|(name, _)| name == package)
+            .map(|(_, version)| version)
+    })
+}
+
+/// Every framework `ProjectTypeDetector` can report, highest precedence first.
+pub fn known_frameworks() -> &'static [&'static str] {
+    FRAMEWORK_PRECEDENCE
+}
+
+fn framework_rank(framework: &str) -> usize {
+    FRAMEWORK_PRECEDENCE
+        .iter()
+        .position(|f| *f == framework)
+        .unwrap_or(FRAMEWORK_PRECEDENCE.len())
+}
+
+fn matches_config_stem(file_name: &str, stem: &str, extensions: &[&str]) -> bool {
+    file_name
+        .strip_prefix(stem)
+        .and_then(|rest| rest.strip_prefix('.'))
+        .is_some_and(|ext| extensions.contains(&ext))
+}
+
+fn detect_framework(path: &str, content: &str) -> Option<&'static str> {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+
+    let mut found: Vec<&'static str> = FRAMEWORK_FILES
+        .iter()
+        .filter(|(stem, extensions, _)| matches_config_stem(file_name, stem, extensions))
+        .map(|(_, _, framework)| *framework)
+        .collect();
+
+    if file_name == "package.json" {
+        found.extend(
+            FRAMEWORK_PACKAGES
+                .iter()
+                .filter(|(package, _)| content.contains(&format!("\"{}\":", package)))
+                .map(|(_, framework)| *framework),
+        );
+    }
+
+    found.into_iter().min_by_key(|framework| framework_rank(framework))
+}
+
+/// Web-technology desktop shells, recognised before the generic indicators so a
+/// Tauri crate isn't read as a Rust CLI tool.
+fn detect_desktop_shell(path: &str, content: &str) -> Option<&'static str> {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    let declares = |dependency: &str| {
+        content.lines().any(|line| {
+            let line = line.trim_start().trim_start_matches('"');
+            line.strip_prefix(dependency)
+                .is_some_and(|rest| rest.trim_start().starts_with(['=', ':']) || rest.starts_with("\":"))
+        })
+    };
+
+    match file_name {
+        "tauri.conf.json" | "Tauri.toml" => Some("Desktop App (Tauri)"),
+        "Cargo.toml" if declares("tauri") => Some("Desktop App (Tauri)"),
+        "package.json" if declares("@tauri-apps/api") => Some("Desktop App (Tauri)"),
+        "package.json" if declares("electron") => Some("Desktop App (Electron)"),
+        _ => None,
+    }
+}
+
+/// Smart-contract toolchains, matched on the file name only: these rank above every
+/// generic indicator, so a README that merely mentions `foundry.toml` must not count.
+fn detect_contract_toolchain(path: &str) -> Option<ProjectType> {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    match file_name {
+        "truffle-config.js" | "foundry.toml" => Some(ProjectType::SmartContract),
+        name if name.starts_with("hardhat.config.") => Some(ProjectType::SmartContract),
+        "Anchor.toml" => Some(ProjectType::SolanaAnchor),
+        "Move.toml" => Some(ProjectType::Move),
+        _ => None,
+    }
+}
+
+/// File names that indicate a project type, checked in this order against the
+/// file name only, so `src/pom.xml.bak` or a README mentioning `Makefile` don't count.
+const PROJECT_TYPE_FILES: &[(&str, ProjectType)] = &[
+    // Backends
+    ("config.ru", ProjectType::RubyBackend),
+    ("main.go", ProjectType::GoBackend),
+    ("index.php", ProjectType::PhpBackend),
+    ("build.sbt", ProjectType::ScalaBackend),
+    // Mobile and desktop apps
+    ("AndroidManifest.xml", ProjectType::MobileApp),
+    ("Info.plist", ProjectType::MobileApp),
+    ("MainActivity.java", ProjectType::MobileApp),
+    ("AppDelegate.swift", ProjectType::MobileApp),
+    ("MainWindow.xaml", ProjectType::DesktopApp),
+    // CLI tools
+    ("Cargo.toml", ProjectType::RustCliTool),
+    ("setup.py", ProjectType::PythonCliTool),
+    ("Makefile", ProjectType::CliTool),
+    ("Program.cs", ProjectType::CSharpCliTool),
+    ("pom.xml", ProjectType::JavaCliTool),
+    ("build.gradle", ProjectType::GradleCliTool),
+    ("Go.mod", ProjectType::GoCliTool),
+    ("Rakefile", ProjectType::RubyCliTool),
+];
+
+fn detect_project_type_and_framework(path: &str, content: &str) -> (Option<ProjectType>, Option<ProjectType>) {
+    if let Some(desktop) = detect_desktop_shell(path, content) {
+        return (Some(ProjectType::from_label(desktop)), None);
+    }
+    if let Some(toolchain) = detect_contract_toolchain(path) {
+        return (Some(toolchain), None);
+    }
+
+    // Check if it's a website
+    if path.ends_with(".html") || path.ends_with(".css") {
+        return match detect_framework(path, content) {
+            Some(framework) => (
+                Some(ProjectType::Website),
+                Some(ProjectType::WebsiteUsing(framework.to_string())),
+            ),
+            None => (Some(ProjectType::Website), Some(ProjectType::StaticWebsite)),
+        };
+    }
+
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    if file_name.ends_with(".desktop") {
+        return (Some(ProjectType::DesktopApp), None);
+    }
+    let project_type = PROJECT_TYPE_FILES
+        .iter()
+        .find(|(name, _)| *name == file_name)
+        .map(|(_, project_type)| project_type.clone());
+    (project_type, None)
+}
+
+#[derive(Debug)]
+struct ConfigTemplate {
+    path: String,
+    keys: Vec<String>,
+}
+
+fn is_config_template(path: &str) -> bool {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    matches!(file_name, ".env.example" | ".env.sample" | ".env.template")
+        || file_name.starts_with("config.example.")
+        || file_name.starts_with("config.sample.")
+}
+
+fn parse_env_keys(content: &str) -> Vec<String> {
+    let mut keys = Vec::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        if let Some((key, _)) = line.split_once('=') {
+            let key = key.trim();
+            let is_valid = !key.is_empty()
+                && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if is_valid && !keys.iter().any(|k| k == key) {
+                keys.push(key.to_string());
+            }
+        }
+    }
+
+    keys
+}
+
+fn detect_config_templates(files: &HashMap<String, Vec<u8>>) -> Vec<ConfigTemplate> {
+    let mut templates: Vec<ConfigTemplate> = files
+        .iter()
+        .filter(|(path, _)| is_config_template(path))
+        .map(|(path, content)| {
+            // Only dotenv templates have a format we can reliably pull keys from
+            let file_name = path.rsplit('/').next().unwrap_or(path);
+            let keys = if file_name.starts_with(".env") {
+                parse_env_keys(&String::from_utf8_lossy(content))
+            } else {
+                Vec::new()
+            };
+            ConfigTemplate { path: path.clone(), keys }
+        })
+        .collect();
+
+    templates.sort_by(|a, b| a.path.cmp(&b.path));
+    templates
+}
+
+#[derive(Debug)]
+struct ReadmeSummary {
+    path: String,
+    title: Option<String>,
+    description: Option<String>,
+}
+
+fn is_readme(path: &str) -> bool {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    file_name.to_ascii_lowercase().starts_with("readme")
+}
+
+// Best-effort: the first `# Heading` and the first paragraph that isn't a heading, badge or HTML
+fn parse_readme(content: &str) -> (Option<String>, Option<String>) {
+    let title = content
+        .lines()
+        .map(str::trim)
+        .find_map(|line| line.strip_prefix("# "))
+        .map(|title| title.trim().to_string());
+
+    let mut paragraph: Vec<&str> = Vec::new();
+    for line in content.lines().map(str::trim) {
+        let is_decoration = line.starts_with('#')
+            || line.starts_with("![")
+            || line.starts_with("[![")
+            || line.starts_with('<')
+            || line.starts_with("===")
+            || line.starts_with("---");
+
+        if line.is_empty() || is_decoration {
+            if !paragraph.is_empty() {
+                break;
+            }
+            continue;
+        }
+        paragraph.push(line);
+    }
+
+    let description = if paragraph.is_empty() {
+        None
+    } else {
+        Some(paragraph.join(" "))
+    };
+
+    (title, description)
+}
+
+fn detect_readme(files: &HashMap<String, Vec<u8>>) -> Option<ReadmeSummary> {
+    // Prefer the top-most README when several directories have one
+    let path = files
+        .keys()
+        .filter(|path| is_readme(path))
+        .min_by_key(|path| (path.matches('/').count(), path.len()))?;
+
+    let (title, description) = parse_readme(&String::from_utf8_lossy(&files[path]));
+    Some(ReadmeSummary {
+        path: path.clone(),
+        title,
+        description,
+    })
+}
+
+const ENTRY_POINT_FILES: &[&str] = &[
+    "main.rs",
+    "main.go",
+    "main.py",
+    "__main__.py",
+    "index.js",
+    "index.ts",
+    "Program.cs",
+    "App.tsx",
+    "App.jsx",
+];
+
+fn detect_entry_points(tree: &[TreeNode]) -> Vec<String> {
+    let mut entry_points: Vec<String> = tree
+        .iter()
+        .filter(|node| node.r#type == "blob")
+        .filter(|node| {
+            let mut segments = node.path.rsplit('/');
+            let file_name = segments.next().unwrap_or(&node.path);
+            let in_bin_dir = segments.next() == Some("bin");
+            in_bin_dir || ENTRY_POINT_FILES.contains(&file_name)
+        })
+        .map(|node| node.path.clone())
+        .collect();
+
+    entry_points.sort();
+    entry_points
+}
+
+pub struct ProjectTypeDetector;
+
+impl Detector for ProjectTypeDetector {
+    fn name(&self) -> &'static str {
+        "project-type"
+    }
+
+    // Each finding carries the paths that indicated it so confidence can be weighed later
+    fn detect(&self, _tree: &[TreeNode], files: &HashMap<String, Vec<u8>>) -> Vec<Finding> {
+        let mut indicators: IndexMap<ProjectType, Vec<String>> = IndexMap::new();
+        let mut framework: Option<(String, String)> = None;
+
+        let mut paths: Vec<&String> = files.keys().collect();
+        paths.sort();
+
+        for path in paths {
+            let content = String::from_utf8_lossy(&files[path]);
+            let (project_type, project_type_with_framework) = detect_project_type_and_framework(path, &content);
+
+            for detected in [project_type, project_type_with_framework].into_iter().flatten() {
+                indicators.entry(detected).or_default().push(path.clone());
+            }
+
+            // Keep the highest-precedence framework regardless of path order
+            if let Some(detected) = detect_framework(path, &content) {
+                let better = framework
+                    .as_ref()
+                    .is_none_or(|(current, _)| framework_rank(detected) < framework_rank(current));
+                if better {
+                    framework = Some((detected.to_string(), path.clone()));
+                }
+            }
+        }
+
+        // A framework declared anywhere (e.g. package.json) upgrades a plain website
+        if let (Some((framework, framework_path)), Some(website)) =
+            (&framework, indicators.get(&ProjectType::Website))
+        {
+            let mut evidence = vec![framework_path.clone()];
+            evidence.extend(website.iter().cloned());
+            indicators.insert(ProjectType::WebsiteUsing(framework.clone()), evidence);
+        }
+
+        let mut findings: Vec<Finding> = indicators
+            .into_iter()
+            .map(|(project_type, evidence)| {
+                Finding::new(FindingKind::ProjectType, project_type.to_string()).with_evidence(evidence)
+            })
+            .collect();
+
+        // Details are [path, version requirement]; the version is absent when no
+        // package.json declares the framework's package
+        if let Some((name, path)) = framework {
+            let mut details = vec![path.clone()];
+            details.extend(framework_version(&name, files));
+            findings.push(
+                Finding::new(FindingKind::Framework, name)
+                    .with_details(details)
+                    .with_evidence(vec![path]),
+            );
+        }
+
+        findings
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Confidence {
+    Low,
+    Medium,
+    High,
+}
+
+impl Confidence {
+    pub fn from_score(score: u32) -> Self {
+        match score {
+            6.. => Confidence::High,
+            3..=5 => Confidence::Medium,
+            _ => Confidence::Low,
+        }
+    }
+}
+
+impl fmt::Display for Confidence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Confidence::Low => "low",
+            Confidence::Medium => "medium",
+            Confidence::High => "high",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+// Incidental files (a lone Makefile) count for little; manifests and frameworks for a lot
+fn indicator_weight(project_type: &str) -> u32 {
+    match project_type {
+        // An extension manifest, desktop shell, site generator or contract toolchain is
+        // decisive and must outrank the web pages these projects also ship, which score
+        // as a website (at most 4 * 3), and the Cargo.toml of every Anchor program
+        t if t.starts_with("Browser Extension")
+            || t.starts_with("Desktop App (")
+            || t.starts_with("Static Site (")
+            || t.starts_with("Smart Contract (")
+            || t == "Solana (Anchor)"
+            || t == "Move" =>
+        {
+            13
+        }
+        t if t.starts_with("Website using") => 4,
+        "Website" | "Static website" | "CLI Tool" | "Ruby CLI Tool" => 1,
+        _ => 3,
+    }
+}
+
+/// Score of a project-type finding; repeated indicators help, but only up to three.
+pub fn indicator_score(finding: &Finding) -> u32 {
+    let count = finding.evidence.len().clamp(1, 3) as u32;
+    indicator_weight(&finding.label) * count
+}
+
+pub struct ReadmeDetector;
+
+impl Detector for ReadmeDetector {
+    fn name(&self) -> &'static str {
+        "readme"
+    }
+
+    // Labelled with the README title (or its path when untitled); the blurb goes in details
+    fn detect(&self, _tree: &[TreeNode], files: &HashMap<String, Vec<u8>>) -> Vec<Finding> {
+        let Some(readme) = detect_readme(files) else {
+            return Vec::new();
+        };
+
+        let label = readme.title.unwrap_or(readme.path);
+        vec![Finding::new(FindingKind::Readme, label)
+            .with_details(readme.description.into_iter().collect())]
+    }
+}
+
+pub struct EntryPointDetector;
+
+impl Detector for EntryPointDetector {
+    fn name(&self) -> &'static str {
+        "entry-points"
+    }
+
+    fn detect(&self, tree: &[TreeNode], _files: &HashMap<String, Vec<u8>>) -> Vec<Finding> {
+        detect_entry_points(tree)
+            .into_iter()
+            .map(|path| Finding::new(FindingKind::EntryPoint, path))
+            .collect()
+    }
+}
+
+pub struct ConfigTemplateDetector;
+
+impl Detector for ConfigTemplateDetector {
+    fn name(&self) -> &'static str {
+        "config-templates"
+    }
+
+    fn detect(&self, _tree: &[TreeNode], files: &HashMap<String, Vec<u8>>) -> Vec<Finding> {
+        detect_config_templates(files)
+            .into_iter()
+            .map(|template| {
+                Finding::new(FindingKind::Configuration, template.path).with_details(template.keys)
+            })
+            .collect()
+    }
+}
+
+pub struct SubmoduleDetector;
+
+impl Detector for SubmoduleDetector {
+    fn name(&self) -> &'static str {
+        "submodules"
+    }
+
+    // Submodules show up in the tree as gitlink entries (mode 160000, type "commit")
+    fn detect(&self, tree: &[TreeNode], _files: &HashMap<String, Vec<u8>>) -> Vec<Finding> {
+        tree.iter()
+            .filter(|node| node.r#type == "commit")
+            .map(|node| Finding::new(FindingKind::Submodule, node.path.clone()))
+            .collect()
+    }
+}
+
+struct Ecosystem {
+    name: &'static str,
+    manifests: &'static [&'static str],
+    lockfiles: &'static [(&'static str, &'static str)],
+}
+
+// Lockfiles are listed with the package manager they imply
+const ECOSYSTEMS: &[Ecosystem] = &[
+    Ecosystem {
+        name: "Node.js",
+        manifests: &["package.json"],
+        lockfiles: &[
+            ("package-lock.json", "npm"),
+            ("npm-shrinkwrap.json", "npm"),
+            ("yarn.lock", "Yarn"),
+            ("pnpm-lock.yaml", "pnpm"),
+            ("bun.lockb", "Bun"),
+        ],
+    },
+    Ecosystem {
+        name: "Python",
+        manifests: &["pyproject.toml", "Pipfile"],
+        lockfiles: &[
+            ("poetry.lock", "Poetry"),
+            ("Pipfile.lock", "Pipenv"),
+            ("uv.lock", "uv"),
+            ("pdm.lock", "PDM"),
+        ],
+    },
+    Ecosystem {
+        name: "Rust",
+        manifests: &["Cargo.toml"],
+        lockfiles: &[("Cargo.lock", "Cargo")],
+    },
+    Ecosystem {
+        name: "Ruby",
+        manifests: &["Gemfile"],
+        lockfiles: &[("Gemfile.lock", "Bundler")],
+    },
+    Ecosystem {
+        name: "PHP",
+        manifests: &["composer.json"],
+        lockfiles: &[("composer.lock", "Composer")],
+    },
+    Ecosystem {
+        name: "Go",
+        manifests: &["go.mod"],
+        lockfiles: &[("go.sum", "Go modules")],
+    },
+];
+
+pub struct LockfileDetector;
+
+impl Detector for LockfileDetector {
+    fn name(&self) -> &'static str {
+        "lockfiles"
+    }
+
+    // Only root-level files count; nested manifests usually belong to fixtures or examples
+    fn detect(&self, tree: &[TreeNode], files: &HashMap<String, Vec<u8>>) -> Vec<Finding> {
+        let has_file = |name: &str| {
+            tree.iter().any(|node| node.r#type == "blob" && node.path == name)
+                || files.contains_key(name)
+        };
+
+        let mut findings = Vec::new();
+        for ecosystem in ECOSYSTEMS {
+            let lockfiles: Vec<_> = ecosystem
+                .lockfiles
+                .iter()
+                .filter(|(lockfile, _)| has_file(lockfile))
+                .collect();
+
+            if !lockfiles.is_empty() {
+                for (lockfile, manager) in lockfiles {
+                    findings.push(
+                        Finding::new(FindingKind::Lockfile, format!("{} ({})", manager, lockfile))
+                            .with_details(vec![lockfile.to_string()])
+                            .with_evidence(vec![lockfile.to_string()]),
+                    );
+                }
+            } else if ecosystem.manifests.iter().any(|manifest| has_file(manifest)) {
+                findings.push(Finding::new(
+                    FindingKind::Lockfile,
+                    format!("{}: no lockfile committed", ecosystem.name),
+                ));
+            }
+        }
+
+        findings
+    }
+}
+
+// `target:` but not `VAR := value` or `VAR ::= value`; pattern rules and specials are skipped
+static MAKE_TARGET: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^([A-Za-z0-9][A-Za-z0-9_./-]*)\s*::?([^:=]|$)").expect("valid regex"));
+
+// Recipe headers look like `name arg1 arg2: deps`; `name := value` is an assignment
+static JUST_RECIPE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^@?([A-Za-z_][A-Za-z0-9_-]*)[^:=]*:([^=]|$)").expect("valid regex"));
+
+fn parse_make_targets(content: &str) -> Vec<String> {
+    let mut targets = Vec::new();
+
+    for line in content.lines() {
+        if let Some(captures) = MAKE_TARGET.captures(line) {
+            let target = captures[1].to_string();
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+    }
+
+    targets
+}
+
+fn parse_just_recipes(content: &str) -> Vec<String> {
+    let mut recipes = Vec::new();
+
+    for line in content.lines() {
+        if let Some(captures) = JUST_RECIPE.captures(line) {
+            let recipe = captures[1].to_string();
+            if !recipes.contains(&recipe) {
+                recipes.push(recipe);
+            }
+        }
+    }
+
+    recipes
+}
+
+fn parse_taskfile_tasks(content: &str) -> Vec<String> {
+    let mut tasks = Vec::new();
+    let mut in_tasks = false;
+    let mut task_indent = None;
+
+    for line in content.lines() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+
+        let indent = line.len() - line.trim_start().len();
+        if indent == 0 {
+            in_tasks = line.trim_end() == "tasks:";
+            continue;
+        }
+
+        if !in_tasks {
+            continue;
+        }
+
+        // Task names are the first indentation level below `tasks:`
+        let task_indent = *task_indent.get_or_insert(indent);
+        if indent == task_indent {
+            if let Some(name) = line.trim().strip_suffix(':') {
+                tasks.push(name.trim_matches(['"', '\'']).to_string());
+            }
+        }
+    }
+
+    tasks
+}
+
+fn parse_npm_scripts(content: &str) -> Vec<String> {
+    let Ok(package) = serde_json::from_str::<serde_json::Value>(content) else {
+        return Vec::new();
+    };
+
+    package["scripts"]
+        .as_object()
+        .map(|scripts| scripts.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+type TaskParser = fn(&str) -> Vec<String>;
+
+const TASK_FILES: &[(&str, TaskParser)] = &[
+    ("Makefile", parse_make_targets),
+    ("makefile", parse_make_targets),
+    ("Justfile", parse_just_recipes),
+    ("justfile", parse_just_recipes),
+    ("Taskfile.yml", parse_taskfile_tasks),
+    ("package.json", parse_npm_scripts),
+];
+
+pub struct TaskDetector;
+
+impl Detector for TaskDetector {
+    fn name(&self) -> &'static str {
+        "tasks"
+    }
+
+    // Only root-level task files; nested ones usually belong to sub-packages
+    fn detect(&self, _tree: &[TreeNode], files: &HashMap<String, Vec<u8>>) -> Vec<Finding> {
+        TASK_FILES
+            .iter()
+            .filter_map(|(file_name, parse)| {
+                let content = files.get(*file_name)?;
+                let tasks = parse(&String::from_utf8_lossy(content));
+                (!tasks.is_empty()).then(|| {
+                    Finding::new(FindingKind::Task, file_name.to_string()).with_details(tasks)
+                })
+            })
+            .collect()
+    }
+}
+
+const ML_MANIFESTS: &[&str] = &[
+    "requirements.txt",
+    "requirements-dev.txt",
+    "pyproject.toml",
+    "setup.py",
+    "Pipfile",
+    "environment.yml",
+];
+
+const ML_FRAMEWORKS: &[(&str, &str)] = &[
+    ("torch", "PyTorch"),
+    ("tensorflow", "TensorFlow"),
+    ("scikit-learn", "scikit-learn"),
+    ("sklearn", "scikit-learn"),
+    ("keras", "Keras"),
+    ("jax", "JAX"),
+];
+
+const MODEL_EXTENSIONS: &[&str] = &[
+    ".pt", ".pth", ".h5", ".onnx", ".safetensors", ".ckpt", ".tflite", ".keras",
+];
+
+// Token match so `sklearn-pandas` doesn't count, but the torch family (`torchvision`) does
+fn mentions_package(content: &str, package: &str) -> bool {
+    content
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+        .any(|token| token == package || (package == "torch" && token.starts_with("torch")))
+}
+
+pub struct MlProjectDetector;
+
+impl Detector for MlProjectDetector {
+    fn name(&self) -> &'static str {
+        "ml-project"
+    }
+
+    fn detect(&self, tree: &[TreeNode], files: &HashMap<String, Vec<u8>>) -> Vec<Finding> {
+        let mut evidence = Vec::new();
+        let mut frameworks: Vec<&str> = Vec::new();
+
+        for manifest in ML_MANIFESTS {
+            let Some(content) = files.get(*manifest) else {
+                continue;
+            };
+            let content = String::from_utf8_lossy(content).to_lowercase();
+            for (package, framework) in ML_FRAMEWORKS {
+                if mentions_package(&content, package) && !frameworks.contains(framework) {
+                    frameworks.push(framework);
+                    evidence.push(manifest.to_string());
+                }
+            }
+        }
+
+        let blobs = || tree.iter().filter(|node| node.r#type == "blob");
+        let model_file = blobs().find(|node| MODEL_EXTENSIONS.iter().any(|ext| node.path.ends_with(ext)));
+        let notebook = blobs().find(|node| node.path.ends_with(".ipynb"));
+        let data_dir = tree
+            .iter()
+            .find(|node| node.r#type == "tree" && (node.path == "data" || node.path == "datasets"));
+
+        let other_signals: Vec<String> = [model_file, notebook, data_dir]
+            .into_iter()
+            .flatten()
+            .map(|node| node.path.clone())
+            .collect();
+
+        // A declared ML dependency is enough; otherwise require two independent signals
+        if frameworks.is_empty() && other_signals.len() < 2 {
+            return Vec::new();
+        }
+
+        evidence.extend(other_signals);
+        evidence.dedup();
+
+        let label = if frameworks.is_empty() {
+            "ML/Data Science".to_string()
+        } else {
+            format!("ML/Data Science ({})", frameworks.join(", "))
+        };
+        vec![Finding::new(FindingKind::ProjectType, label).with_evidence(evidence)]
+    }
+}
+
+/// Keys only a WebExtension manifest declares; PWA manifests (`start_url`,
+/// `display`, `icons`) share the file name but never carry these.
+const EXTENSION_KEYS: &[&str] = &["background", "content_scripts", "browser_action", "page_action"];
+
+fn extension_manifest_version(content: &[u8]) -> Option<u64> {
+    let manifest: serde_json::Value = serde_json::from_slice(content).ok()?;
+    let version = manifest.get("manifest_version")?.as_u64()?;
+    EXTENSION_KEYS
+        .iter()
+        .any(|key| manifest.get(key).is_some())
+        .then_some(version)
+}
+
+pub struct BrowserExtensionDetector;
+
+impl Detector for BrowserExtensionDetector {
+    fn name(&self) -> &'static str {
+        "browser-extension"
+    }
+
+    fn detect(&self, _tree: &[TreeNode], files: &HashMap<String, Vec<u8>>) -> Vec<Finding> {
+        let mut manifests: Vec<(&String, u64)> = files
+            .iter()
+            .filter(|(path, _)| path.rsplit('/').next() == Some("manifest.json"))
+            .filter_map(|(path, content)| Some((path, extension_manifest_version(content)?)))
+            .collect();
+        // Prefer the shallowest manifest when a repo vendors extensions in subfolders
+        manifests.sort_by_key(|(path, _)| (path.matches('/').count(), path.to_string()));
+
+        match manifests.first() {
+            Some((path, version)) => vec![Finding::new(
+                FindingKind::ProjectType,
+                format!("Browser Extension (Manifest V{})", version),
+            )
+            .with_evidence(vec![path.to_string()])],
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Files that describe a reproducible development environment, by tool.
+const DEV_ENVIRONMENTS: &[(&str, &[&str])] = &[
+    (
+        "Devcontainer",
+        &[".devcontainer/devcontainer.json", ".devcontainer.json"],
+    ),
+    ("Gitpod", &[".gitpod.yml", ".gitpod.Dockerfile"]),
+    ("Nix", &["flake.nix", "shell.nix", "default.nix"]),
+];
+
+pub struct DevEnvironmentDetector;
+
+impl Detector for DevEnvironmentDetector {
+    fn name(&self) -> &'static str {
+        "dev-environment"
+    }
+
+    fn detect(&self, tree: &[TreeNode], _files: &HashMap<String, Vec<u8>>) -> Vec<Finding> {
+        DEV_ENVIRONMENTS
+            .iter()
+            .filter_map(|(tool, indicators)| {
+                let evidence: Vec<String> = tree
+                    .iter()
+                    .filter(|node| node.r#type == "blob" && indicators.contains(&node.path.as_str()))
+                    .map(|node| node.path.clone())
+                    .collect();
+                (!evidence.is_empty())
+                    .then(|| Finding::new(FindingKind::DevEnvironment, *tool).with_evidence(evidence))
+            })
+            .collect()
+    }
+}
+
+fn plural(count: usize, singular: &str, plural: &str) -> String {
+    format!("{} {}", count, if count == 1 { singular } else { plural })
+}
+
+static PROTO_SERVICE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^\s*service\s+\w+\s*\{").expect("invalid service pattern"));
+static PROTO_RPC: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^\s*rpc\s+\w+\s*\(").expect("invalid rpc pattern"));
+
+pub struct GrpcDetector;
+
+impl Detector for GrpcDetector {
+    fn name(&self) -> &'static str {
+        "grpc"
+    }
+
+    fn detect(&self, _tree: &[TreeNode], files: &HashMap<String, Vec<u8>>) -> Vec<Finding> {
+        let mut services = 0;
+        let mut rpcs = 0;
+        let mut evidence = Vec::new();
+
+        let mut protos: Vec<&String> = files.keys().filter(|path| path.ends_with(".proto")).collect();
+        protos.sort();
+
+        for path in protos {
+            let content = String::from_utf8_lossy(&files[path]);
+            let file_services = PROTO_SERVICE.find_iter(&content).count();
+            if file_services > 0 {
+                services += file_services;
+                rpcs += PROTO_RPC.find_iter(&content).count();
+                evidence.push(path.clone());
+            }
+        }
+
+        if services == 0 {
+            return Vec::new();
+        }
+
+        let label = format!(
+            "gRPC: {}, {}",
+            plural(services, "service", "services"),
+            plural(rpcs, "RPC", "RPCs")
+        );
+        vec![Finding::new(FindingKind::ApiSurface, label).with_evidence(evidence)]
+    }
+}
+
+const NATIVE_EXTENSIONS: &[&str] = &[".c", ".cc", ".cpp", ".cxx", ".h", ".hpp", ".rs", ".go"];
+
+/// Platform names and the directory names / Go file suffixes that point at them.
+const PLATFORM_HINTS: &[(&str, &[&str], &str)] = &[
+    ("Windows", &["windows", "win32", "win64", "win"], "_windows.go"),
+    ("Linux", &["linux"], "_linux.go"),
+    ("macOS", &["darwin", "macos", "osx"], "_darwin.go"),
+    ("Android", &["android"], "_android.go"),
+    ("iOS", &["ios"], "_ios.go"),
+    ("FreeBSD", &["freebsd"], "_freebsd.go"),
+    ("WebAssembly", &["wasm"], "_wasm.go"),
+];
+
+/// Platform conditionals in CMakeLists.txt, matched case-sensitively as CMake writes them.
+const CMAKE_PLATFORMS: &[(&str, &[&str])] = &[
+    ("Windows", &["WIN32", "MSVC", "\"Windows\""]),
+    ("Linux", &["\"Linux\"", "LINUX"]),
+    ("macOS", &["APPLE", "\"Darwin\""]),
+    ("Android", &["ANDROID"]),
+    ("iOS", &["\"iOS\""]),
+];
+
+pub struct TargetPlatformDetector;
+
+impl Detector for TargetPlatformDetector {
+    fn name(&self) -> &'static str {
+        "target-platform"
+    }
+
+    fn detect(&self, tree: &[TreeNode], files: &HashMap<String, Vec<u8>>) -> Vec<Finding> {
+        let is_native = tree
+            .iter()
+            .any(|node| node.r#type == "blob" && NATIVE_EXTENSIONS.iter().any(|ext| node.path.ends_with(ext)));
+        if !is_native {
+            return Vec::new();
+        }
+
+        let mut platforms: IndexMap<&str, Vec<String>> = IndexMap::new();
+        let mut cross = Vec::new();
+
+        for node in tree {
+            let name = node.path.rsplit('/').next().unwrap_or(&node.path);
+            for (platform, dirs, go_suffix) in PLATFORM_HINTS {
+                let hit = match node.r#type.as_str() {
+                    "tree" => dirs.contains(&name.to_ascii_lowercase().as_str()),
+                    "blob" => name.ends_with(go_suffix),
+                    _ => false,
+                };
+                if hit {
+                    platforms.entry(platform).or_default().push(node.path.clone());
+                }
+            }
+
+            if node.r#type == "blob"
+                && (name.ends_with(".toolchain") || name.ends_with(".toolchain.cmake") || name == "Cross.toml")
+            {
+                cross.push(node.path.clone());
+            }
+        }
+
+        let mut cmake_files: Vec<&String> = files
+            .keys()
+            .filter(|path| path.rsplit('/').next() == Some("CMakeLists.txt"))
+            .collect();
+        cmake_files.sort();
+        for path in cmake_files {
+            let content = String::from_utf8_lossy(&files[path]);
+            for (platform, markers) in CMAKE_PLATFORMS {
+                if markers.iter().any(|marker| content.contains(marker)) {
+                    platforms.entry(platform).or_default().push(path.clone());
+                }
+            }
+        }
+
+        let mut findings: Vec<Finding> = platforms
+            .into_iter()
+            .map(|(platform, evidence)| Finding::new(FindingKind::TargetPlatform, platform).with_evidence(evidence))
+            .collect();
+        if !cross.is_empty() {
+            findings.push(Finding::new(FindingKind::TargetPlatform, "Cross-compilation").with_evidence(cross));
+        }
+        findings
+    }
+}
+
+/// Root-level files that identify a static-site generator on their own.
+const SSG_CONFIGS: &[(&str, &[&str])] = &[
+    ("Hugo", &["hugo.toml", "hugo.yaml", "hugo.json"]),
+    ("Gatsby", &["gatsby-config.js", "gatsby-config.ts", "gatsby-config.mjs"]),
+    ("Astro", &["astro.config.mjs", "astro.config.js", "astro.config.ts", "astro.config.cjs", "astro.config.mts"]),
+    ("Eleventy", &[".eleventy.js", "eleventy.config.js", "eleventy.config.cjs", "eleventy.config.mjs"]),
+];
+
+pub struct StaticSiteDetector;
+
+impl StaticSiteDetector {
+    // Generic config names need a companion directory or dependency to count
+    fn detect_generic(tree: &[TreeNode], files: &HashMap<String, Vec<u8>>) -> Option<(&'static str, Vec<String>)> {
+        let has_blob = |path: &str| tree.iter().any(|n| n.r#type == "blob" && n.path == path);
+        let has_dir = |path: &str| tree.iter().any(|n| n.r#type == "tree" && n.path == path);
+        let mentions = |path: &str, needle: &str| {
+            files
+                .get(path)
+                .is_some_and(|content| String::from_utf8_lossy(content).contains(needle))
+        };
+
+        if has_blob("_config.yml") && (has_dir("_posts") || has_dir("_layouts") || mentions("Gemfile", "jekyll")) {
+            return Some(("Jekyll", vec!["_config.yml".to_string()]));
+        }
+
+        let hugo_config = ["config.toml", "config.yaml"].into_iter().find(|c| has_blob(c))?;
+        (has_dir("content") && (has_dir("layouts") || has_dir("archetypes") || has_dir("themes")))
+            .then(|| ("Hugo", vec![hugo_config.to_string(), "content".to_string()]))
+    }
+}
+
+impl Detector for StaticSiteDetector {
+    fn name(&self) -> &'static str {
+        "static-site"
+    }
+
+    fn detect(&self, tree: &[TreeNode], files: &HashMap<String, Vec<u8>>) -> Vec<Finding> {
+        let configured = SSG_CONFIGS.iter().find_map(|(generator, configs)| {
+            let evidence: Vec<String> = tree
+                .iter()
+                .filter(|n| n.r#type == "blob" && configs.contains(&n.path.as_str()))
+                .map(|n| n.path.clone())
+                .collect();
+            (!evidence.is_empty()).then_some((*generator, evidence))
+        });
+
+        match configured.or_else(|| Self::detect_generic(tree, files)) {
+            Some((generator, evidence)) => vec![Finding::new(
+                FindingKind::ProjectType,
+                format!("Static Site ({})", generator),
+            )
+            .with_evidence(evidence)],
+            None => Vec::new(),
+        }
+    }
+}
+
+// Which API-doc generator a file configures, if any; generic names need a telltale line
+fn doc_generator(path: &str, content: &str) -> Option<&'static str> {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    let in_docs_dir = path.starts_with("docs/") || path.starts_with("doc/");
+
+    match file_name {
+        "Cargo.toml" if content.contains("[package.metadata.docs.rs]") => Some("rustdoc"),
+        "conf.py" if in_docs_dir || content.contains("sphinx") => Some("Sphinx"),
+        "mkdocs.yml" | "mkdocs.yaml" => Some("MkDocs"),
+        "typedoc.json" => Some("TypeDoc"),
+        "package.json" if content.contains("\"typedoc\"") => Some("TypeDoc"),
+        "pom.xml" if content.contains("maven-javadoc-plugin") => Some("Javadoc"),
+        "build.gradle" | "build.gradle.kts" if content.contains("javadoc") => Some("Javadoc"),
+        _ => None,
+    }
+}
+
+/// Generated API documentation (rustdoc on docs.rs, Sphinx, TypeDoc, MkDocs, Javadoc),
+/// as opposed to a hand-built documentation site.
+pub struct DocsToolDetector;
+
+impl Detector for DocsToolDetector {
+    fn name(&self) -> &'static str {
+        "docs-tool"
+    }
+
+    fn detect(&self, _tree: &[TreeNode], files: &HashMap<String, Vec<u8>>) -> Vec<Finding> {
+        let mut generators: IndexMap<&'static str, Vec<String>> = IndexMap::new();
+
+        let mut paths: Vec<&String> = files.keys().collect();
+        paths.sort();
+        for path in paths {
+            let content = String::from_utf8_lossy(&files[path]);
+            if let Some(generator) = doc_generator(path, &content) {
+                generators.entry(generator).or_default().push(path.clone());
+            }
+        }
+
+        generators
+            .into_iter()
+            .map(|(generator, evidence)| Finding::new(FindingKind::DocsTool, generator).with_evidence(evidence))
+            .collect()
+    }
+}
+
+// Which coverage tool a file configures; generic manifests need the tool's own section
+fn coverage_tool(path: &str, content: &str) -> Option<&'static str> {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+
+    match file_name {
+        "codecov.yml" | ".codecov.yml" => Some("Codecov"),
+        ".coveralls.yml" => Some("Coveralls"),
+        ".coveragerc" => Some("coverage.py"),
+        "pyproject.toml" | "setup.cfg" if content.contains("[tool.coverage") || content.contains("[coverage:") => {
+            Some("coverage.py")
+        }
+        "tarpaulin.toml" | ".tarpaulin.toml" => Some("tarpaulin"),
+        ".nycrc" | ".nycrc.json" | ".nycrc.yml" => Some("nyc"),
+        "package.json" if content.contains("\"coverageThreshold\"") => Some("Jest"),
+        name if name.starts_with("jest.config.") && content.contains("coverageThreshold") => Some("Jest"),
+        "pom.xml" if content.contains("jacoco-maven-plugin") => Some("JaCoCo"),
+        "build.gradle" | "build.gradle.kts" if content.contains("jacoco") => Some("JaCoCo"),
+        _ => None,
+    }
+}
+
+/// Coverage tracking (Codecov, tarpaulin, coverage.py, Jest thresholds, ...), a
+/// signal of engineering rigor separate from whether tests exist at all.
+pub struct CoverageToolDetector;
+
+impl Detector for CoverageToolDetector {
+    fn name(&self) -> &'static str {
+        "coverage-tool"
+    }
+
+    fn detect(&self, tree: &[TreeNode], files: &HashMap<String, Vec<u8>>) -> Vec<Finding> {
+        let mut tools: IndexMap<&'static str, Vec<String>> = IndexMap::new();
+
+        let mut paths: Vec<&String> = files.keys().collect();
+        paths.sort();
+        for path in paths {
+            let content = String::from_utf8_lossy(&files[path]);
+            if let Some(tool) = coverage_tool(path, &content) {
+                tools.entry(tool).or_default().push(path.clone());
+            }
+        }
+
+        // Committed reports count too, though they name no tool
+        if tree.iter().any(|node| node.r#type == "tree" && node.path == "coverage") {
+            tools.entry("coverage reports").or_default().push("coverage".to_string());
+        }
+
+        tools
+            .into_iter()
+            .map(|(tool, evidence)| Finding::new(FindingKind::CoverageTool, tool).with_evidence(evidence))
+            .collect()
+    }
+}
+
+fn is_changelog(path: &str) -> bool {
+    let file_name = path.rsplit('/').next().unwrap_or(path).to_ascii_lowercase();
+    ["changelog", "changes", "history", "news", "releases"]
+        .iter()
+        .any(|stem| file_name == *stem || file_name.strip_prefix(stem).is_some_and(|rest| rest.starts_with('.')))
+}
+
+static RELEASE_HEADING: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(?:#{1,6}\s*(?:.*?\s)?|=+\s*|\*\s*|.*?\brelease\s+)?\[?\s*(?:version\s+)?(v?\d+\.\d+(?:\.\d+)*(?:-[0-9a-z.]+)?)\b")
+        .expect("invalid version heading pattern")
+});
+static RELEASE_DATE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\d{4}-\d{2}-\d{2}").expect("invalid date pattern"));
+
+// Best-effort: the first version heading ("## [2.3.0] - 2024-05-01", "v2.3.0 (2024-05-01)",
+// "Version 2.3.0") and the date on that line, skipping an "Unreleased" section
+fn parse_latest_release(content: &str) -> Option<(String, Option<String>)> {
+    content.lines().map(str::trim).find_map(|line| {
+        let version = RELEASE_HEADING.captures(line)?.get(1)?.as_str();
+        let date = RELEASE_DATE.find(line).map(|date| date.as_str().to_string());
+        Some((version.to_string(), date))
+    })
+}
+
+/// The top-most changelog and its latest entry. Labelled with the changelog's
+/// path; details hold the version and, when the heading has one, its date.
+pub struct ChangelogDetector;
+
+impl Detector for ChangelogDetector {
+    fn name(&self) -> &'static str {
+        "changelog"
+    }
+
+    fn detect(&self, _tree: &[TreeNode], files: &HashMap<String, Vec<u8>>) -> Vec<Finding> {
+        let Some(path) = files
+            .keys()
+            .filter(|path| is_changelog(path))
+            .min_by_key(|path| (path.matches('/').count(), path.len()))
+        else {
+            return Vec::new();
+        };
+
+        let details = match parse_latest_release(&String::from_utf8_lossy(&files[path])) {
+            Some((version, date)) => std::iter::once(version).chain(date).collect(),
+            None => Vec::new(),
+        };
+        vec![Finding::new(FindingKind::Changelog, path.clone())
+            .with_details(details)
+            .with_evidence(vec![path.clone()])]
+    }
+}
+
+/// Directories whose children are named after locales, e.g. `locales/de/` or `i18n/fr.json`.
+const LOCALE_DIRS: &[&str] = &["locale", "locales", "i18n", "l10n", "lang", "langs", "translations"];
+
+// Which translation tooling a file belongs to; manifests need the library as a dependency
+fn i18n_tool(path: &str, content: &str) -> Option<&'static str> {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    let extension = file_name.rsplit_once('.').map_or("", |(_, ext)| ext);
+
+    match (file_name, extension) {
+        (_, "po" | "pot" | "mo") => Some("gettext"),
+        (_, "arb") | ("l10n.yaml", _) => Some("Flutter (ARB)"),
+        (_, "xlf" | "xliff") => Some("XLIFF"),
+        (name, _) if name.starts_with("next-i18next.config.") || name.starts_with("i18next.config.") => Some("i18next"),
+        ("package.json", _) if content.contains("\"i18next\"") || content.contains("\"react-i18next\"") => {
+            Some("i18next")
+        }
+        ("package.json", _) if content.contains("\"react-intl\"") => Some("react-intl"),
+        ("package.json", _) if content.contains("\"vue-i18n\"") => Some("vue-i18n"),
+        _ => None,
+    }
+}
+
+// "en", "pt-BR", "zh_Hans"; three-letter names are too often words like "src" to accept
+fn is_locale_code(name: &str) -> bool {
+    let (language, region) = match name.split_once(['-', '_']) {
+        Some((language, region)) => (language, Some(region)),
+        None => (name, None),
+    };
+    language.len() == 2
+        && language.bytes().all(|b| b.is_ascii_lowercase())
+        && region.is_none_or(|region| {
+            matches!(region.len(), 2 | 4) && region.bytes().all(|b| b.is_ascii_alphabetic())
+        })
+}
+
+// The locale a translation path is for: the entry under a locale directory, the
+// directory holding gettext's `LC_MESSAGES`, or the suffix of `app_en.arb` / `messages.fr.xlf`
+fn locale_of(path: &str) -> Option<&str> {
+    let segments: Vec<&str> = path.split('/').collect();
+
+    if let Some(index) = segments.iter().position(|segment| *segment == "LC_MESSAGES") {
+        let locale = segments.get(index.checked_sub(1)?)?;
+        return is_locale_code(locale).then_some(*locale);
+    }
+
+    let parent = segments
+        .iter()
+        .position(|segment| LOCALE_DIRS.contains(&segment.to_ascii_lowercase().as_str()));
+    if let Some(entry) = parent.and_then(|index| segments.get(index + 1)) {
+        let name = entry.split('.').next().unwrap_or(entry);
+        if is_locale_code(name) {
+            return Some(name);
+        }
+    }
+
+    let file_name = segments.last()?;
+    let (stem, extension) = file_name.rsplit_once('.')?;
+    let candidate = match extension {
+        "arb" => stem.rsplit_once('_').map_or(stem, |(_, suffix)| suffix),
+        "po" | "xlf" | "xliff" => stem.rsplit_once('.').map_or(stem, |(_, suffix)| suffix),
+        _ => return None,
+    };
+    is_locale_code(candidate).then_some(candidate)
+}
+
+/// Localization: translation tooling (gettext, ARB, XLIFF, i18next, ...) as `I18n`
+/// findings, and one `Locale` finding per locale found in translation paths.
+pub struct I18nDetector;
+
+impl Detector for I18nDetector {
+    fn name(&self) -> &'static str {
+        "i18n"
+    }
+
+    fn detect(&self, tree: &[TreeNode], files: &HashMap<String, Vec<u8>>) -> Vec<Finding> {
+        let mut tools: IndexMap<&'static str, Vec<String>> = IndexMap::new();
+
+        let mut paths: Vec<&String> = files.keys().collect();
+        paths.sort();
+        for path in paths {
+            let content = String::from_utf8_lossy(&files[path]);
+            if let Some(tool) = i18n_tool(path, &content) {
+                tools.entry(tool).or_default().push(path.clone());
+            }
+        }
+
+        let mut locales: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for node in tree.iter().filter(|node| node.r#type == "blob") {
+            if let Some(locale) = locale_of(&node.path) {
+                locales.entry(locale).or_default().push(node.path.clone());
+            }
+        }
+
+        let tool_findings = tools
+            .into_iter()
+            .map(|(tool, evidence)| Finding::new(FindingKind::I18n, tool).with_evidence(evidence));
+        let locale_findings = locales
+            .into_iter()
+            .map(|(locale, evidence)| Finding::new(FindingKind::Locale, locale).with_evidence(evidence));
+        tool_findings.chain(locale_findings).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn env_template_keys_are_listed_once_in_order() {
+        let content = "# Database\nDATABASE_URL=postgres://localhost\nexport API_KEY=\n\nAPI_KEY=again\nnot a key\nBAD-KEY=1\n";
+        assert_eq!(parse_env_keys(content), ["DATABASE_URL", "API_KEY"]);
+    }
+
+    #[test]
+    fn config_templates_are_recognised_by_name() {
+        assert!(is_config_template("app/.env.example"));
+        assert!(is_config_template("config.sample.yml"));
+        assert!(!is_config_template(".env"));
+        assert!(!is_config_template("src/config.rs"));
+    }
+
+    fn node(path: &str, r#type: &str) -> TreeNode {
+        TreeNode {
+            path: path.to_string(),
+            mode: "100644".to_string(),
+            r#type: r#type.to_string(),
+            sha: String::new(),
+            size: None,
+            url: None,
+        }
+    }
+
+    #[test]
+    fn entry_points_are_known_names_and_bin_files() {
+        let tree = [
+            node("src/main.rs", "blob"),
+            node("bin/tool", "blob"),
+            node("bin", "tree"),
+            node("web/src/App.tsx", "blob"),
+            node("src/lib.rs", "blob"),
+            node("tools/bin/nested/run.sh", "blob"),
+        ];
+        assert_eq!(detect_entry_points(&tree), ["bin/tool", "src/main.rs", "web/src/App.tsx"]);
+    }
+
+    #[test]
+    fn readme_title_and_first_paragraph_skip_badges() {
+        let content = "# ProjectChecker\n\n[![CI](https://example.com/badge.svg)](https://example.com)\n\nAnalyze the file types\nof a repository.\n\n## Usage\n";
+        assert_eq!(
+            parse_readme(content),
+            (
+                Some("ProjectChecker".to_string()),
+                Some("Analyze the file types of a repository.".to_string())
+            )
+        );
+        assert_eq!(parse_readme("<p align=\"center\"></p>\n"), (None, None));
+    }
+
+    #[test]
+    fn root_manifests_report_their_lockfile_or_its_absence() {
+        let tree = [
+            node("package.json", "blob"),
+            node("yarn.lock", "blob"),
+            node("Cargo.toml", "blob"),
+            node("examples/Gemfile", "blob"),
+        ];
+        let labels: Vec<String> = LockfileDetector
+            .detect(&tree, &HashMap::new())
+            .into_iter()
+            .map(|finding| finding.label)
+            .collect();
+        assert_eq!(labels, ["Yarn (yarn.lock)", "Rust: no lockfile committed"]);
+    }
+
+    #[test]
+    fn make_targets_skip_assignments() {
+        let makefile = "CC := gcc\nVERSION ::= 1.0\nbuild: deps\n\tcargo build\ntest:\n\tcargo test\n.PHONY: build test\nbuild:\n";
+        assert_eq!(parse_make_targets(makefile), ["build", "test"]);
+    }
+
+    #[test]
+    fn just_recipes_skip_assignments() {
+        let justfile = "version := \"1.0\"\n@build target:\n    cargo build\ntest: build\n    cargo test\n";
+        assert_eq!(parse_just_recipes(justfile), ["build", "test"]);
+    }
+
+    #[test]
+    fn taskfile_tasks_are_the_first_level_under_tasks() {
+        let taskfile = "version: '3'\ntasks:\n  build:\n    cmds:\n      - go build\n  \"lint\":\n    deps: [build]\nvars:\n  GREETING: hi\n";
+        assert_eq!(parse_taskfile_tasks(taskfile), ["build", "lint"]);
+    }
+
+    #[test]
+    fn npm_scripts_come_from_the_scripts_object() {
+        assert_eq!(parse_npm_scripts(r#"{"scripts": {"build": "tsc", "test": "jest"}}"#), ["build", "test"]);
+        assert!(parse_npm_scripts("not json").is_empty());
+    }
+
+    #[test]
+    fn vue_wins_over_react_when_both_are_declared() {
+        let package = r#"{"dependencies": {"react": "^18.2.0", "vue": "^3.4.0"}}"#;
+        assert_eq!(detect_framework("package.json", package), Some("Vue.js"));
+    }
+
+    #[test]
+    fn framework_files_match_the_exact_file_name() {
+        assert_eq!(detect_framework("web/next.config.js", ""), Some("Next.js"));
+        assert_eq!(detect_framework("web/next.config.js.bak", ""), None);
+        assert_eq!(detect_framework("docs/angular.json.md", ""), None);
+    }
+
+    #[test]
+    fn grpc_services_and_rpcs_are_counted_across_proto_files() {
+        let files: HashMap<String, Vec<u8>> = [
+            ("proto/users.proto", "service Users {\n  rpc Get (GetRequest) returns (User);\n  rpc List(ListRequest) returns (stream User);\n}\n"),
+            ("proto/health.proto", "service Health {\n  rpc Check (Ping) returns (Pong);\n}\n// rpc Commented (A) returns (B);\n"),
+            ("proto/types.proto", "message User { string name = 1; }\n"),
+        ]
+        .into_iter()
+        .map(|(path, content)| (path.to_string(), content.as_bytes().to_vec()))
+        .collect();
+
+        let findings = GrpcDetector.detect(&[], &files);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].label, "gRPC: 2 services, 3 RPCs");
+        assert_eq!(findings[0].evidence, ["proto/health.proto", "proto/users.proto"]);
+    }
+
+    #[test]
+    fn proto_files_without_services_report_nothing() {
+        let files: HashMap<String, Vec<u8>> =
+            HashMap::from([("types.proto".to_string(), b"message Empty {}\n".to_vec())]);
+        assert!(GrpcDetector.detect(&[], &files).is_empty());
+    }
+
+    #[test]
+    fn framework_config_files_match_every_extension() {
+        for (stem, framework) in [("next.config", "Next.js"), ("nuxt.config", "Nuxt"), ("vue.config", "Vue.js")] {
+            for extension in JS_CONFIG_EXTENSIONS {
+                let path = format!("web/{}.{}", stem, extension);
+                assert_eq!(detect_framework(&path, ""), Some(framework), "{}", path);
+            }
+        }
+        assert_eq!(detect_framework("angular.json", ""), Some("Angular"));
+        assert_eq!(detect_framework("next.config.json", ""), None);
+        assert_eq!(detect_framework("next.config.ts.bak", ""), None);
+    }
+
+    #[test]
+    fn keep_a_changelog_headings_give_version_and_date() {
+        let content = "# Changelog\n\n## [Unreleased]\n\n## [1.2.3] - 2024-05-01\n### Added\n\n## [1.2.2] - 2024-04-01\n";
+        assert_eq!(parse_latest_release(content), Some(("1.2.3".to_string(), Some("2024-05-01".to_string()))));
+    }
+
+    #[test]
+    fn version_word_and_underlined_headings_are_recognised() {
+        assert_eq!(parse_latest_release("Changes\n\nVersion 1.2.3\n-------------\n"), Some(("1.2.3".to_string(), None)));
+        assert_eq!(parse_latest_release("=== v1.2\n\n* Fixed a crash\n"), Some(("v1.2".to_string(), None)));
+    }
+
+    #[test]
+    fn changelogs_without_a_version_have_no_release() {
+        assert_eq!(parse_latest_release("# Changelog\n\nNothing released yet.\n"), None);
+    }
+
+    #[test]
+    fn locales_come_from_locale_dirs_gettext_and_file_suffixes() {
+        assert_eq!(locale_of("public/locales/de/common.json"), Some("de"));
+        assert_eq!(locale_of("src/i18n/pt-BR.json"), Some("pt-BR"));
+        assert_eq!(locale_of("po/fr/LC_MESSAGES/app.po"), Some("fr"));
+        assert_eq!(locale_of("lib/l10n/app_es.arb"), Some("es"));
+        assert_eq!(locale_of("translations/messages.ja.xlf"), Some("ja"));
+        // Three-letter and capitalised directory names are not locale codes
+        assert_eq!(locale_of("lang/src/index.ts"), None);
+        assert_eq!(locale_of("locales/EN/index.json"), None);
+        assert_eq!(locale_of("src/main.rs"), None);
+    }
+
+    #[test]
+    fn i18n_tools_and_locales_are_reported_separately() {
+        let tree = [
+            node("locales/en/app.po", "blob"),
+            node("locales/de/app.po", "blob"),
+            node("locales/de", "tree"),
+        ];
+        let files: HashMap<String, Vec<u8>> = [
+            ("locales/en/app.po", ""),
+            ("locales/de/app.po", ""),
+            ("package.json", r#"{"dependencies": {"react-i18next": "^14.0.0"}}"#),
+        ]
+        .into_iter()
+        .map(|(path, content)| (path.to_string(), content.as_bytes().to_vec()))
+        .collect();
+
+        let findings: Vec<(FindingKind, String)> = I18nDetector
+            .detect(&tree, &files)
+            .into_iter()
+            .map(|finding| (finding.kind, finding.label))
+            .collect();
+        assert_eq!(
+            findings,
+            [
+                (FindingKind::I18n, "gettext".to_string()),
+                (FindingKind::I18n, "i18next".to_string()),
+                (FindingKind::Locale, "de".to_string()),
+                (FindingKind::Locale, "en".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn project_type_files_match_exact_file_names() {
+        let project_type = |path: &str, content: &str| detect_project_type_and_framework(path, content).0;
+        assert_eq!(project_type("server/pom.xml", ""), Some(ProjectType::JavaCliTool));
+        assert_eq!(project_type("build.gradle", ""), Some(ProjectType::GradleCliTool));
+        assert_eq!(project_type("share/app.desktop", ""), Some(ProjectType::DesktopApp));
+        assert_eq!(project_type("src/pom.xml.bak", ""), None);
+        assert_eq!(project_type("README.md", "see Makefile"), None);
+    }
+
+    #[test]
+    fn electron_apps_are_recognised_from_their_package_json() {
+        let package = "{\n  \"devDependencies\": {\n    \"electron\": \"^30.0.0\"\n  }\n}\n";
+        assert_eq!(
+            detect_project_type_and_framework("package.json", package).0,
+            Some(ProjectType::from_label("Desktop App (Electron)"))
+        );
+        assert_eq!(detect_project_type_and_framework("docs/electron-microscopy.md", "electron beam").0, None);
+    }
+}
@@ -1,23 +1,267 @@
+use owo_colors::{AnsiColors, OwoColorize, Stream::Stdout};
+
 use crate::api::TreeNode;
+use crate::detectors::{Finding, FindingKind};
+
+/// The lines `print_tree` prints, uncolored, each paired with whether it is a
+/// directory. Ordering by path components is a pre-order walk of the hierarchy
+/// (a directory, then everything under it), so no recursion or per-directory
+/// filtering is needed however deep or wide the tree is.
+pub fn tree_lines(tree: &[TreeNode], level: usize) -> Vec<(String, bool)> {
+    let mut nodes: Vec<&TreeNode> = tree.iter().collect();
+    nodes.sort_by(|a, b| a.path.split('/').cmp(b.path.split('/')));
 
+    nodes
+        .into_iter()
+        .filter_map(|node| {
+            let indent = "  ".repeat(level + node.path.matches('/').count());
+            match node.r#type.as_str() {
+                "tree" => Some((format!("{}{}/", indent, node.path), true)),
+                "blob" => Some((format!("{}{}", indent, node.path), false)),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Prints each node once, indented by its depth, with directories highlighted.
 pub fn print_tree(tree: &[TreeNode], level: usize) {
-    let indent = "  ".repeat(level);
-    for node in tree {
-        if node.r#type == "tree" {
-            println!("{}{}/", indent, node.path);
-            // Recursively print subdirectories
-            let subdir_tree: Vec<TreeNode> = tree.iter()
-                .filter_map(|n| {
-                    if n.path.starts_with(&node.path) && n.path != node.path {
-                        Some(n.clone())
-                    } else {
-                        None
-                    }
-                })
-                .collect();
-            print_tree(&subdir_tree, level + 1);
-        } else if node.r#type == "blob" {
-            println!("{}{}", indent, node.path);
-        }
-    }
-}
\ No newline at end of file
+    for (line, is_dir) in tree_lines(tree, level) {
+        if is_dir {
+            let (indent, dir) = line.split_at(line.len() - line.trim_start().len());
+            println!("{}{}", indent, dir.if_supports_color(Stdout, |t| t.bold().blue().to_string()));
+        } else {
+            println!("{}", line);
+        }
+    }
+}
+
+/// `--tree-format flat`: every file path on its own line, sorted, without directories.
+pub fn print_flat_tree(tree: &[TreeNode]) {
+    let mut paths: Vec<&str> = tree
+        .iter()
+        .filter(|node| node.r#type == "blob")
+        .map(|node| node.path.as_str())
+        .collect();
+    paths.sort_unstable();
+
+    for path in paths {
+        println!("{}", path);
+    }
+}
+
+const TYPE_PALETTE: &[AnsiColors] = &[
+    AnsiColors::Cyan,
+    AnsiColors::Magenta,
+    AnsiColors::Yellow,
+    AnsiColors::Green,
+    AnsiColors::Blue,
+    AnsiColors::Red,
+    AnsiColors::BrightCyan,
+    AnsiColors::BrightMagenta,
+];
+
+// A file type keeps the same palette slot everywhere it is shown
+pub(crate) fn type_color_index(file_type: &str) -> usize {
+    file_type.bytes().map(usize::from).sum::<usize>() % TYPE_PALETTE.len()
+}
+
+// Colors are only emitted for a terminal without NO_COLOR set, so piped output stays plain
+pub fn paint_file_type(file_type: &str) -> String {
+    let index = type_color_index(file_type);
+    file_type
+        .if_supports_color(Stdout, |t| t.color(TYPE_PALETTE[index]).to_string())
+        .to_string()
+}
+
+pub fn paint_headline(text: &str) -> String {
+    text.if_supports_color(Stdout, |t| t.bold().green().to_string())
+        .to_string()
+}
+
+pub fn display_readme(readme: Option<&Finding>) {
+    match readme {
+        Some(readme) => match readme.details.first() {
+            Some(description) => println!("Project: {} — {}", readme.label, description),
+            None => println!("Project: {}", readme.label),
+        },
+        None => println!("README: none found"),
+    }
+}
+
+pub fn display_changelog(changelog: Option<&Finding>) {
+    let Some(changelog) = changelog else {
+        println!("Changelog: none found");
+        return;
+    };
+
+    match (changelog.details.first(), changelog.details.get(1)) {
+        (Some(version), Some(date)) => println!("Latest changelog entry: {} ({})", version, date),
+        (Some(version), None) => println!("Latest changelog entry: {}", version),
+        _ => println!("Changelog: {} (no version headings)", changelog.label),
+    }
+}
+
+pub fn display_findings(findings: &[Finding]) {
+    let of_kind = |kind: FindingKind| findings.iter().filter(move |f| f.kind == kind);
+
+    for finding in of_kind(FindingKind::Framework) {
+        match finding.details.get(1) {
+            Some(version) => println!("Framework: {} {}", finding.label, version),
+            None => println!("Framework: {}", finding.label),
+        }
+    }
+
+    if of_kind(FindingKind::EntryPoint).next().is_some() {
+        println!("Entry points:");
+        for finding in of_kind(FindingKind::EntryPoint) {
+            println!("  {}", finding.label);
+        }
+    }
+
+    if of_kind(FindingKind::Configuration).next().is_some() {
+        println!("Required configuration:");
+        for finding in of_kind(FindingKind::Configuration) {
+            if finding.details.is_empty() {
+                println!("  {}", finding.label);
+            } else {
+                println!("  {}: {}", finding.label, finding.details.join(", "));
+            }
+        }
+    }
+
+    if of_kind(FindingKind::Task).next().is_some() {
+        println!("Tasks:");
+        for finding in of_kind(FindingKind::Task) {
+            println!("  {}: {}", finding.label, finding.details.join(", "));
+        }
+    }
+
+    for finding in of_kind(FindingKind::Lockfile) {
+        println!("Lockfile: {}", finding.label);
+    }
+
+    let dev_environments: Vec<&str> = of_kind(FindingKind::DevEnvironment)
+        .map(|f| f.label.as_str())
+        .collect();
+    if !dev_environments.is_empty() {
+        println!("Dev environment: {}", dev_environments.join(" / "));
+    }
+
+    let doc_tools: Vec<&str> = of_kind(FindingKind::DocsTool)
+        .map(|f| f.label.as_str())
+        .collect();
+    if !doc_tools.is_empty() {
+        println!("Docs: {}", doc_tools.join(" / "));
+    }
+
+    let coverage_tools: Vec<&str> = of_kind(FindingKind::CoverageTool)
+        .map(|f| f.label.as_str())
+        .collect();
+    if !coverage_tools.is_empty() {
+        println!("Coverage tooling: {}", coverage_tools.join(" / "));
+    }
+
+    let locales: Vec<&str> = of_kind(FindingKind::Locale).map(|f| f.label.as_str()).collect();
+    let i18n_tools: Vec<&str> = of_kind(FindingKind::I18n).map(|f| f.label.as_str()).collect();
+    match (locales.len(), i18n_tools.is_empty()) {
+        (0, true) => {}
+        (0, false) => println!("i18n: {}", i18n_tools.join(" / ")),
+        (count, _) => {
+            let via = if i18n_tools.is_empty() {
+                String::new()
+            } else {
+                format!(" via {}", i18n_tools.join(" / "))
+            };
+            println!(
+                "i18n: {} {} detected ({}){}",
+                count,
+                if count == 1 { "locale" } else { "locales" },
+                locales.join(", "),
+                via
+            );
+        }
+    }
+
+    let platforms: Vec<&str> = of_kind(FindingKind::TargetPlatform)
+        .map(|f| f.label.as_str())
+        .collect();
+    if !platforms.is_empty() {
+        println!("Target platforms: {}", platforms.join(", "));
+    }
+
+    let requirements: Vec<String> = of_kind(FindingKind::RuntimeVersion)
+        .map(|f| format!("{} {}", f.label, f.details.first().map_or("*", String::as_str)))
+        .collect();
+    if !requirements.is_empty() {
+        println!("Requires: {}", requirements.join(", "));
+    }
+
+    for finding in of_kind(FindingKind::ApiSurface) {
+        println!("{}", finding.label);
+    }
+
+    let submodule_count = of_kind(FindingKind::Submodule).count();
+    if submodule_count > 0 {
+        println!("Submodules: {}", submodule_count);
+    }
+}
+
+/// `--explain`: the paths behind each verdict, so a misdetection can be traced.
+pub fn display_evidence(findings: &[Finding]) {
+    let explained: Vec<&Finding> = findings.iter().filter(|f| !f.evidence.is_empty()).collect();
+    if explained.is_empty() {
+        return;
+    }
+
+    println!("Evidence:");
+    for finding in explained {
+        println!("  {}: {}", finding.label, finding.evidence.join(", "));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(path: &str, r#type: &str) -> TreeNode {
+        TreeNode {
+            path: path.to_string(),
+            mode: String::new(),
+            r#type: r#type.to_string(),
+            sha: String::new(),
+            size: None,
+            url: None,
+        }
+    }
+
+    #[test]
+    fn tree_lines_are_a_pre_order_walk_of_a_large_tree() {
+        // 100 directories, each with one subdirectory holding 98 files: 10,000 nodes
+        let mut tree = Vec::new();
+        let mut expected = Vec::new();
+        for dir in 0..100 {
+            tree.push(node(&format!("d{:02}", dir), "tree"));
+            tree.push(node(&format!("d{:02}/inner", dir), "tree"));
+            expected.push((format!("d{:02}/", dir), true));
+            expected.push((format!("  d{:02}/inner/", dir), true));
+            for file in 0..98 {
+                tree.push(node(&format!("d{:02}/inner/f{:02}.rs", dir, file), "blob"));
+                expected.push((format!("    d{:02}/inner/f{:02}.rs", dir, file), false));
+            }
+        }
+        assert_eq!(tree.len(), 10_000);
+        tree.reverse();
+
+        assert_eq!(tree_lines(&tree, 0), expected);
+    }
+
+    #[test]
+    fn tree_lines_start_at_the_given_level_and_skip_submodules() {
+        let tree = [node("a.txt", "blob"), node("vendor/lib", "commit"), node("b", "tree")];
+        assert_eq!(
+            tree_lines(&tree, 1),
+            [("  a.txt".to_string(), false), ("  b/".to_string(), true)]
+        );
+    }
+}
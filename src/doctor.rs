@@ -0,0 +1,176 @@
+use std::error::Error;
+use std::path::Path;
+
+use owo_colors::{OwoColorize, Stream::Stdout};
+use serde::Deserialize;
+
+use crate::api::{fetch_rate_limit, parse_json, parse_mappings, EMBEDDED_MAPPINGS, MAPPINGS_PATH};
+use crate::cli::Options;
+use crate::client::{ClientConfig, GitHubClient};
+
+const API_BASE: &str = "https://api.github.com";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// One line of the `doctor` checklist; `hint` says how to fix anything but a pass.
+struct Check {
+    name: &'static str,
+    status: Status,
+    detail: String,
+    hint: Option<&'static str>,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Pass,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, hint: &'static str) -> Self {
+        Self {
+            name,
+            status: Status::Warn,
+            detail: detail.into(),
+            hint: Some(hint),
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, hint: &'static str) -> Self {
+        Self {
+            name,
+            status: Status::Fail,
+            detail: detail.into(),
+            hint: Some(hint),
+        }
+    }
+
+    fn print(&self) {
+        let marker = match self.status {
+            Status::Pass => "[ok]  ".if_supports_color(Stdout, |t| t.green().to_string()).to_string(),
+            Status::Warn => "[warn]".if_supports_color(Stdout, |t| t.yellow().to_string()).to_string(),
+            Status::Fail => "[FAIL]".if_supports_color(Stdout, |t| t.red().to_string()).to_string(),
+        };
+        println!("{} {}: {}", marker, self.name, self.detail);
+        if let Some(hint) = self.hint {
+            println!("       {}", hint);
+        }
+    }
+}
+
+fn check_mappings() -> Check {
+    let count = |mappings: &crate::api::FileMappings| {
+        mappings.file_types().categories().iter().map(|(_, types)| types.len()).sum::<usize>()
+    };
+
+    match std::fs::read_to_string(Path::new(MAPPINGS_PATH)) {
+        Ok(json) => match parse_mappings(&json, MAPPINGS_PATH) {
+            Ok(mappings) => Check::pass("Mappings", format!("{} ({} file types)", MAPPINGS_PATH, count(&mappings))),
+            Err(e) => Check::warn(
+                "Mappings",
+                format!("{}; the built-in mappings will be used", e),
+                "Fix the JSON syntax of extensions.json, or delete it to use the built-in mappings.",
+            ),
+        },
+        Err(_) => match parse_mappings(EMBEDDED_MAPPINGS, "built-in mappings") {
+            Ok(mappings) => Check::pass("Mappings", format!("built-in ({} file types)", count(&mappings))),
+            Err(e) => Check::fail("Mappings", e, "This build is broken; reinstall it."),
+        },
+    }
+}
+
+async fn check_connectivity(client: &GitHubClient) -> Check {
+    match client.get(API_BASE).send().await {
+        Ok(res) => Check::pass("API", format!("{} reachable (HTTP {})", API_BASE, res.status().as_u16())),
+        Err(e) => Check::fail(
+            "API",
+            format!("cannot reach {}: {}", API_BASE, e),
+            "Check the network connection, or set --proxy / HTTPS_PROXY when behind a proxy.",
+        ),
+    }
+}
+
+#[derive(Deserialize)]
+struct User {
+    login: String,
+}
+
+async fn check_token(client: &GitHubClient) -> Check {
+    if !client.has_token() {
+        return Check::warn(
+            "Token",
+            "none set; requests are limited to 60 per hour",
+            "Set GITHUB_TOKEN or pass --token to raise the limit to 5000 per hour.",
+        );
+    }
+
+    let res = match client.get(&format!("{}/user", API_BASE)).send().await {
+        Ok(res) => res,
+        Err(e) => return Check::fail("Token", format!("could not be checked: {}", e), "Fix connectivity first."),
+    };
+    match res.status() {
+        status if status.is_success() => match parse_json::<User>(res).await {
+            Ok(user) => Check::pass("Token", format!("valid, authenticated as {}", user.login)),
+            Err(e) => Check::warn("Token", format!("accepted, but the response was unexpected: {}", e), "Retry later."),
+        },
+        reqwest::StatusCode::UNAUTHORIZED => Check::fail(
+            "Token",
+            "rejected (401 Unauthorized)",
+            "The token is invalid, expired or revoked; create a new one and update GITHUB_TOKEN/--token.",
+        ),
+        status => Check::warn(
+            "Token",
+            format!("GET /user returned {}", status),
+            "Fine-grained tokens may lack user access; repository analysis can still work.",
+        ),
+    }
+}
+
+async fn check_rate_limit(client: &GitHubClient) -> Check {
+    match fetch_rate_limit(client).await {
+        Ok(rate_limit) if rate_limit.resources.core.remaining == 0 => Check::fail(
+            "Rate limit",
+            format!("exhausted (0/{})", rate_limit.resources.core.limit),
+            "Wait for the reset shown by `ratelimit`, or use a token with quota left.",
+        ),
+        Ok(rate_limit) => Check::pass(
+            "Rate limit",
+            format!("{}/{} requests remaining", rate_limit.resources.core.remaining, rate_limit.resources.core.limit),
+        ),
+        Err(e) => Check::fail("Rate limit", format!("could not be read: {}", e), "Fix connectivity or the token first."),
+    }
+}
+
+/// `doctor`: checks mappings, connectivity, the token and the rate limit, and prints
+/// a checklist with a hint for every problem. Fails when any check failed.
+pub async fn run_doctor(options: &Options) -> Result<(), Box<dyn Error>> {
+    let client = GitHubClient::new(&ClientConfig::from_options(options))?;
+
+    let mut checks = vec![check_mappings()];
+    let connectivity = check_connectivity(&client).await;
+    let online = connectivity.status == Status::Pass;
+    checks.push(connectivity);
+    // Without connectivity the remaining checks would only repeat the same error
+    if online {
+        checks.push(check_token(&client).await);
+        checks.push(check_rate_limit(&client).await);
+    }
+
+    for check in &checks {
+        check.print();
+    }
+
+    let failed = checks.iter().filter(|check| check.status == Status::Fail).count();
+    if failed > 0 {
+        return Err(format!("{} {} failed", failed, if failed == 1 { "check" } else { "checks" }).into());
+    }
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use std::error::Error;
+use std::fmt;
+
+/// Maximum number of characters of a response body kept for error messages.
+const SNIPPET_LEN: usize = 200;
+
+#[derive(Debug)]
+pub enum ApiError {
+    /// The request itself failed or its body could not be read.
+    Http(reqwest::Error),
+    /// The response body was not the JSON we expected (e.g. an HTML error page).
+    Parse {
+        status: reqwest::StatusCode,
+        snippet: String,
+        source: serde_json::Error,
+    },
+    /// HTTP 404: the repository does not exist or the token cannot see it.
+    RepoNotFound(String),
+    /// HTTP 403 without an exhausted quota, e.g. SSO enforcement or missing token scopes.
+    Forbidden(String),
+    /// HTTP 451: access is blocked for legal reasons, such as a DMCA takedown.
+    Unavailable(String),
+}
+
+impl ApiError {
+    pub fn parse(status: reqwest::StatusCode, body: &str, source: serde_json::Error) -> Self {
+        ApiError::Parse {
+            status,
+            snippet: truncate(body.trim(), SNIPPET_LEN),
+            source,
+        }
+    }
+
+    /// Maps the statuses a user can act on to a typed error; `None` for anything else.
+    pub fn from_status(
+        status: reqwest::StatusCode,
+        headers: &reqwest::header::HeaderMap,
+        repo: &str,
+        body: &str,
+    ) -> Option<Self> {
+        let quota_exhausted = headers
+            .get("x-ratelimit-remaining")
+            .is_some_and(|remaining| remaining == "0");
+
+        match status.as_u16() {
+            404 => Some(ApiError::RepoNotFound(repo.to_string())),
+            403 if !quota_exhausted => Some(ApiError::Forbidden(truncate(body.trim(), SNIPPET_LEN))),
+            451 => Some(ApiError::Unavailable(block_reason(body))),
+            _ => None,
+        }
+    }
+}
+
+// 451 bodies carry `{"message", "block": {"reason", "html_url"}}`; fall back to the raw body
+fn block_reason(body: &str) -> String {
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(body) else {
+        return truncate(body.trim(), SNIPPET_LEN);
+    };
+    let block = &json["block"];
+    let reason = block["reason"]
+        .as_str()
+        .or(json["message"].as_str())
+        .unwrap_or("no reason given");
+
+    match block["html_url"].as_str() {
+        Some(url) => format!("{} (see {})", reason, url),
+        None => reason.to_string(),
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::Http(e) => write!(f, "HTTP request failed: {}", e),
+            ApiError::Parse { status, snippet, source } => write!(
+                f,
+                "Failed to parse response (HTTP {}): {} - body: {}",
+                status, source, snippet
+            ),
+            ApiError::RepoNotFound(repo) => write!(
+                f,
+                "Repository {} not found; check the URL, or pass --token if it is private",
+                repo
+            ),
+            ApiError::Forbidden(message) => write!(
+                f,
+                "Access forbidden (HTTP 403): {}; the token may lack scopes or need SSO authorization",
+                message
+            ),
+            ApiError::Unavailable(reason) => {
+                write!(f, "Repository unavailable for legal reasons (HTTP 451): {}", reason)
+            }
+        }
+    }
+}
+
+impl Error for ApiError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ApiError::Http(e) => Some(e),
+            ApiError::Parse { source, .. } => Some(source),
+            ApiError::RepoNotFound(_) | ApiError::Forbidden(_) | ApiError::Unavailable(_) => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for ApiError {
+    fn from(e: reqwest::Error) -> Self {
+        ApiError::Http(e)
+    }
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((idx, _)) => format!("{}...", &text[..idx]),
+        None => text.to_string(),
+    }
+}
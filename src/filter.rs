@@ -0,0 +1,102 @@
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::collections::HashSet;
+use std::error::Error;
+
+use crate::api::TreeNode;
+use crate::cli::Options;
+
+/// Include/exclude globs from the command line. Includes are applied first, then
+/// excludes, so `--include 'src/**' --exclude '*.snap'` keeps src minus snapshots.
+#[derive(Debug, Clone, Default)]
+pub struct PathFilter {
+    include: Option<GlobSet>,
+    exclude: Option<GlobSet>,
+}
+
+impl PathFilter {
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self, Box<dyn Error>> {
+        Ok(Self {
+            include: build_glob_set(include)?,
+            exclude: build_glob_set(exclude)?,
+        })
+    }
+
+    pub fn from_options(options: &Options) -> Result<Self, Box<dyn Error>> {
+        Self::new(&options.include, &options.exclude)
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.include.is_some() || self.exclude.is_some()
+    }
+
+    pub fn matches(&self, path: &str) -> bool {
+        let included = self.include.as_ref().is_none_or(|set| set.is_match(path));
+        let excluded = self.exclude.as_ref().is_some_and(|set| set.is_match(path));
+        included && !excluded
+    }
+
+    // Directories and submodules are kept so the tree structure stays intact
+    pub fn apply(&self, tree: &[TreeNode]) -> Vec<TreeNode> {
+        tree.iter()
+            .filter(|node| node.r#type != "blob" || self.matches(&node.path))
+            .cloned()
+            .collect()
+    }
+}
+
+/// `--sample`: keeps `count` randomly chosen files plus every directory. Files are
+/// drawn from the path-sorted list, so a given `seed` picks the same files every run.
+pub fn sample_blobs(tree: &[TreeNode], count: usize, seed: Option<u64>) -> Vec<TreeNode> {
+    let mut rng = match seed {
+        Some(seed) => fastrand::Rng::with_seed(seed),
+        None => fastrand::Rng::new(),
+    };
+    let mut blobs: Vec<&TreeNode> = tree.iter().filter(|node| node.r#type == "blob").collect();
+    blobs.sort_by(|a, b| a.path.cmp(&b.path));
+    let chosen: HashSet<&str> = rng
+        .choose_multiple(blobs.into_iter(), count)
+        .into_iter()
+        .map(|node| node.path.as_str())
+        .collect();
+
+    tree.iter()
+        .filter(|node| node.r#type != "blob" || chosen.contains(node.path.as_str()))
+        .cloned()
+        .collect()
+}
+
+/// `--strip-prefix`: `packages/web/src/main.ts` reads as `src/main.ts`. Paths outside the
+/// prefix are returned unchanged.
+pub fn strip_path_prefix<'a>(path: &'a str, prefix: &str) -> &'a str {
+    let prefix = prefix.trim_matches('/');
+    if prefix.is_empty() {
+        return path;
+    }
+    path.strip_prefix(prefix)
+        .and_then(|rest| rest.strip_prefix('/'))
+        .unwrap_or(path)
+}
+
+// The prefix directory itself is dropped; everything below it moves up to the root
+pub fn strip_tree_prefix(tree: &[TreeNode], prefix: &str) -> Vec<TreeNode> {
+    tree.iter()
+        .filter(|node| node.path != prefix.trim_matches('/'))
+        .map(|node| TreeNode {
+            path: strip_path_prefix(&node.path, prefix).to_string(),
+            ..node.clone()
+        })
+        .collect()
+}
+
+fn build_glob_set(patterns: &[String]) -> Result<Option<GlobSet>, Box<dyn Error>> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = Glob::new(pattern).map_err(|e| format!("Invalid glob '{}': {}", pattern, e))?;
+        builder.add(glob);
+    }
+    Ok(Some(builder.build()?))
+}
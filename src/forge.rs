@@ -0,0 +1,414 @@
+use reqwest::Url;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+use tracing::{info, warn};
+
+use crate::api::{FetchedFiles, GitTree, SkipRecord, TreeNode};
+use crate::client::GitHubClient;
+
+/// Code hosts the tool knows how to fetch from, picked from the URL host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Host {
+    GitHub,
+    GitLab,
+    Bitbucket,
+}
+
+impl Host {
+    pub fn from_domain(domain: &str) -> Option<Self> {
+        match domain.trim_start_matches("www.") {
+            "github.com" => Some(Host::GitHub),
+            "gitlab.com" => Some(Host::GitLab),
+            "bitbucket.org" => Some(Host::Bitbucket),
+            _ => None,
+        }
+    }
+}
+
+/// "Fetch tree + fetch files" for one repository on some host. Everything after
+/// that (filtering, classification, detectors, output) is host-agnostic.
+// Only ever awaited in place by the CLI, so the futures need no Send bound
+#[allow(async_fn_in_trait)]
+pub trait Forge {
+    async fn default_branch(&self) -> Result<String, Box<dyn Error>>;
+
+    /// Every file and directory of `branch`, with `url` pointing at something
+    /// `fetch_file` can download.
+    async fn fetch_tree(&self, branch: &str) -> Result<Vec<TreeNode>, Box<dyn Error>>;
+
+    async fn fetch_file(&self, node: &TreeNode) -> Result<Vec<u8>, Box<dyn Error>>;
+
+    /// Downloads every blob in `tree`, logging and skipping the ones that fail.
+    async fn fetch_files(&self, tree: &[TreeNode]) -> FetchedFiles {
+        let mut files = HashMap::new();
+        let mut skipped = Vec::new();
+        let mut failed = 0;
+
+        for node in tree.iter().filter(|node| node.is_file()) {
+            match self.fetch_file(node).await {
+                Ok(content) => {
+                    files.insert(node.path.clone(), content);
+                }
+                Err(e) => {
+                    warn!("Skipping {}: {}", node.path, e);
+                    skipped.push(SkipRecord::new(&node.path, format!("fetch failed: {}", e)));
+                    failed += 1;
+                }
+            }
+        }
+
+        if failed > 0 {
+            warn!("{} of {} files could not be fetched", failed, failed + files.len());
+        }
+
+        (files, skipped)
+    }
+}
+
+fn node(path: String, mode: String, is_dir: bool, sha: String, size: Option<u64>, url: Option<String>) -> TreeNode {
+    TreeNode {
+        path,
+        mode,
+        r#type: if is_dir { "tree" } else { "blob" }.to_string(),
+        sha,
+        size,
+        url,
+    }
+}
+
+// Builds `base` + percent-encoded segments, so a segment may itself contain '/'
+fn api_url(base: &str, segments: &[&str]) -> Result<Url, Box<dyn Error>> {
+    let mut url = Url::parse(base)?;
+    url.path_segments_mut()
+        .map_err(|_| format!("Cannot extend URL: {}", base))?
+        .extend(segments);
+    Ok(url)
+}
+
+async fn get_ok(client: &GitHubClient, url: &str) -> Result<reqwest::Response, Box<dyn Error>> {
+    let response = client.get_with_retry(url).await?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("Request to {} failed: {} - {}", url, status, body.trim()).into());
+    }
+    Ok(response)
+}
+
+async fn get_raw(client: &GitHubClient, node: &TreeNode) -> Result<Vec<u8>, Box<dyn Error>> {
+    let url = node.url.as_deref().ok_or("missing URL")?;
+    Ok(get_ok(client, url).await?.bytes().await?.to_vec())
+}
+
+pub struct GitHubForge<'a> {
+    client: &'a GitHubClient,
+    owner: String,
+    repo: String,
+    concurrency: usize,
+}
+
+impl<'a> GitHubForge<'a> {
+    pub fn new(client: &'a GitHubClient, owner: &str, repo: &str) -> Self {
+        Self {
+            client,
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            concurrency: 8,
+        }
+    }
+
+    /// Directory requests in flight when a truncated tree has to be walked.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+}
+
+impl Forge for GitHubForge<'_> {
+    async fn default_branch(&self) -> Result<String, Box<dyn Error>> {
+        Ok(crate::api::fetch_repo_info(self.client, &self.owner, &self.repo)
+            .await?
+            .default_branch)
+    }
+
+    async fn fetch_tree(&self, branch: &str) -> Result<Vec<TreeNode>, Box<dyn Error>> {
+        let branch = if crate::api::is_short_sha(branch) {
+            crate::api::resolve_short_sha(self.client, &self.owner, &self.repo, branch).await?
+        } else {
+            branch.to_string()
+        };
+        let response = crate::api::fetch_tree(self.client, &self.owner, &self.repo, &branch).await?;
+        if !response.status().is_success() {
+            return Err(format!("Failed to fetch the repo tree: {}", response.status()).into());
+        }
+        let tree: GitTree = crate::api::parse_json(response).await?;
+        if tree.truncated {
+            // Forges back the library builder and --watch, where a live counter would get in the way
+            return crate::api::walk_tree(self.client, &tree.url, self.concurrency, false).await;
+        }
+        Ok(tree.tree)
+    }
+
+    async fn fetch_file(&self, node: &TreeNode) -> Result<Vec<u8>, Box<dyn Error>> {
+        crate::api::fetch_file(self.client, node)
+            .await?
+            .ok_or_else(|| "missing URL".into())
+    }
+}
+
+/// GitLab addresses projects by their URL-encoded full path, which may include
+/// nested groups (`group/subgroup/project`).
+pub struct GitLabForge<'a> {
+    client: &'a GitHubClient,
+    project: String,
+}
+
+impl<'a> GitLabForge<'a> {
+    pub fn new(client: &'a GitHubClient, namespace: &str, project: &str) -> Self {
+        Self {
+            client,
+            project: format!("{}/{}", namespace, project),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GitLabProject {
+    default_branch: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GitLabTreeEntry {
+    id: String,
+    path: String,
+    mode: String,
+    #[serde(rename = "type")]
+    entry_type: String,
+}
+
+const GITLAB_API: &str = "https://gitlab.com/api/v4";
+
+impl Forge for GitLabForge<'_> {
+    async fn default_branch(&self) -> Result<String, Box<dyn Error>> {
+        let url = api_url(GITLAB_API, &["projects", &self.project])?;
+        let project: GitLabProject = get_ok(self.client, url.as_str()).await?.json().await?;
+        Ok(project.default_branch.unwrap_or_else(|| "main".to_string()))
+    }
+
+    async fn fetch_tree(&self, branch: &str) -> Result<Vec<TreeNode>, Box<dyn Error>> {
+        let mut nodes = Vec::new();
+        let mut page = "1".to_string();
+
+        // The tree endpoint is paginated; X-Next-Page is empty on the last page
+        loop {
+            let mut url = api_url(GITLAB_API, &["projects", &self.project, "repository", "tree"])?;
+            url.query_pairs_mut()
+                .append_pair("recursive", "true")
+                .append_pair("per_page", "100")
+                .append_pair("ref", branch)
+                .append_pair("page", &page);
+            info!("Tree URL: {}", url);
+
+            let response = get_ok(self.client, url.as_str()).await?;
+            let next_page = response
+                .headers()
+                .get("x-next-page")
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or("")
+                .to_string();
+            let entries: Vec<GitLabTreeEntry> = response.json().await?;
+
+            for entry in entries {
+                let is_dir = entry.entry_type == "tree";
+                let raw_url = if is_dir {
+                    None
+                } else {
+                    let mut raw = api_url(
+                        GITLAB_API,
+                        &["projects", &self.project, "repository", "files", &entry.path, "raw"],
+                    )?;
+                    raw.query_pairs_mut().append_pair("ref", branch);
+                    Some(raw.to_string())
+                };
+                nodes.push(node(entry.path, entry.mode, is_dir, entry.id, None, raw_url));
+            }
+
+            if next_page.is_empty() {
+                break;
+            }
+            page = next_page;
+        }
+
+        Ok(nodes)
+    }
+
+    async fn fetch_file(&self, node: &TreeNode) -> Result<Vec<u8>, Box<dyn Error>> {
+        get_raw(self.client, node).await
+    }
+}
+
+pub struct BitbucketForge<'a> {
+    client: &'a GitHubClient,
+    workspace: String,
+    repo: String,
+}
+
+impl<'a> BitbucketForge<'a> {
+    pub fn new(client: &'a GitHubClient, workspace: &str, repo: &str) -> Self {
+        Self {
+            client,
+            workspace: workspace.to_string(),
+            repo: repo.to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct BitbucketRepo {
+    mainbranch: Option<BitbucketBranch>,
+}
+
+#[derive(Deserialize)]
+struct BitbucketBranch {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct BitbucketPage {
+    values: Vec<BitbucketEntry>,
+    next: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BitbucketEntry {
+    path: String,
+    #[serde(rename = "type")]
+    entry_type: String,
+    size: Option<u64>,
+}
+
+const BITBUCKET_API: &str = "https://api.bitbucket.org/2.0";
+
+impl Forge for BitbucketForge<'_> {
+    async fn default_branch(&self) -> Result<String, Box<dyn Error>> {
+        let url = api_url(BITBUCKET_API, &["repositories", &self.workspace, &self.repo])?;
+        let repo: BitbucketRepo = get_ok(self.client, url.as_str()).await?.json().await?;
+        Ok(repo.mainbranch.map_or_else(|| "main".to_string(), |b| b.name))
+    }
+
+    // The src endpoint lists one directory at a time, so walk it breadth-first
+    async fn fetch_tree(&self, branch: &str) -> Result<Vec<TreeNode>, Box<dyn Error>> {
+        let mut nodes = Vec::new();
+        let mut pending = vec![String::new()];
+
+        while let Some(dir) = pending.pop() {
+            let mut segments = vec!["repositories", &self.workspace, &self.repo, "src", branch];
+            segments.extend(dir.split('/').filter(|s| !s.is_empty()));
+            let mut url = api_url(BITBUCKET_API, &segments)?;
+            // A trailing slash asks for the directory listing rather than file content
+            url.path_segments_mut().map_err(|_| "Cannot extend URL")?.push("");
+            let mut next = Some(url.to_string());
+
+            while let Some(page_url) = next {
+                info!("Tree URL: {}", page_url);
+                let page: BitbucketPage = get_ok(self.client, &page_url).await?.json().await?;
+                for entry in page.values {
+                    let is_dir = entry.entry_type == "commit_directory";
+                    let raw_url = if is_dir {
+                        pending.push(entry.path.clone());
+                        None
+                    } else {
+                        let mut segments = vec!["repositories", &self.workspace, &self.repo, "src", branch];
+                        segments.extend(entry.path.split('/'));
+                        Some(api_url(BITBUCKET_API, &segments)?.to_string())
+                    };
+                    nodes.push(node(entry.path, String::new(), is_dir, String::new(), entry.size, raw_url));
+                }
+                next = page.next;
+            }
+        }
+
+        nodes.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(nodes)
+    }
+
+    async fn fetch_file(&self, node: &TreeNode) -> Result<Vec<u8>, Box<dyn Error>> {
+        get_raw(self.client, node).await
+    }
+}
+
+/// A repository checked out on disk. Nothing goes over the network; `.git` is skipped.
+pub struct LocalForge {
+    root: PathBuf,
+}
+
+impl LocalForge {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(metadata: &fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_metadata: &fs::Metadata) -> bool {
+    false
+}
+
+impl Forge for LocalForge {
+    // There is no branch to pick; the working tree is analyzed as it is
+    async fn default_branch(&self) -> Result<String, Box<dyn Error>> {
+        Ok("HEAD".to_string())
+    }
+
+    // Explicit stack rather than recursion, with git's modes so symlinks and
+    // executables are reported like they are for remote trees
+    async fn fetch_tree(&self, _branch: &str) -> Result<Vec<TreeNode>, Box<dyn Error>> {
+        let mut nodes = Vec::new();
+        let mut pending = vec![(self.root.clone(), String::new())];
+
+        while let Some((dir, prefix)) = pending.pop() {
+            let mut entries: Vec<fs::DirEntry> = fs::read_dir(&dir)?.collect::<Result<_, _>>()?;
+            entries.sort_by_key(|entry| entry.file_name());
+
+            for entry in entries {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if name == ".git" {
+                    continue;
+                }
+                let path = format!("{}{}", prefix, name);
+                let metadata = fs::symlink_metadata(entry.path())?;
+
+                let mode = if metadata.file_type().is_symlink() {
+                    TreeNode::SYMLINK_MODE
+                } else if metadata.is_dir() {
+                    "040000"
+                } else if is_executable(&metadata) {
+                    TreeNode::EXECUTABLE_MODE
+                } else {
+                    "100644"
+                };
+
+                if metadata.is_dir() {
+                    pending.push((entry.path(), format!("{}/", path)));
+                }
+                let size = (!metadata.is_dir()).then_some(metadata.len());
+                nodes.push(node(path, mode.to_string(), metadata.is_dir(), String::new(), size, None));
+            }
+        }
+
+        nodes.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(nodes)
+    }
+
+    async fn fetch_file(&self, node: &TreeNode) -> Result<Vec<u8>, Box<dyn Error>> {
+        Ok(fs::read(self.root.join(&node.path))?)
+    }
+}
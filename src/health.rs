@@ -0,0 +1,170 @@
+use crate::api::RepoAnalysis;
+use crate::detectors::FindingKind;
+use crate::licenses::is_license_file;
+
+#[derive(Debug, Clone)]
+pub struct HealthSignal {
+    /// Stable identifier for machine-readable output
+    pub id: &'static str,
+    pub name: &'static str,
+    pub present: bool,
+    pub points: u8,
+}
+
+#[derive(Debug, Clone)]
+pub struct HealthReport {
+    pub score: u8,
+    pub signals: Vec<HealthSignal>,
+}
+
+/// Share of Unknown files below which the mappings are considered to cover the repo.
+const MAX_UNKNOWN_RATIO: f64 = 0.2;
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+pub fn is_test_path(path: &str) -> bool {
+    let name = file_name(path);
+    path.split('/')
+        .any(|segment| matches!(segment, "test" | "tests" | "spec" | "specs" | "__tests__"))
+        || name.starts_with("test_")
+        || name.contains("_test.")
+        || name.contains(".test.")
+        || name.contains(".spec.")
+}
+
+pub fn is_ci_path(path: &str) -> bool {
+    path.starts_with(".github/workflows/")
+        || path.starts_with(".circleci/")
+        || matches!(
+            path,
+            ".gitlab-ci.yml"
+                | ".travis.yml"
+                | "Jenkinsfile"
+                | "azure-pipelines.yml"
+                | "bitbucket-pipelines.yml"
+                | ".drone.yml"
+        )
+}
+
+pub fn compute_health(analysis: &RepoAnalysis) -> HealthReport {
+    let paths = || analysis.files.iter().map(|f| f.path.as_str());
+
+    let unknown = analysis
+        .files
+        .iter()
+        .filter(|f| f.file_type == "Unknown")
+        .count();
+    let low_unknown = !analysis.files.is_empty()
+        && (unknown as f64 / analysis.files.len() as f64) < MAX_UNKNOWN_RATIO;
+
+    let has_lockfile = analysis
+        .findings_of(FindingKind::Lockfile)
+        .any(|f| !f.details.is_empty());
+
+    let signals = vec![
+        HealthSignal {
+            id: "readme",
+            name: "README",
+            present: analysis.findings_of(FindingKind::Readme).next().is_some(),
+            points: 20,
+        },
+        HealthSignal {
+            id: "tests",
+            name: "tests",
+            present: paths().any(is_test_path),
+            points: 20,
+        },
+        HealthSignal {
+            id: "ci",
+            name: "CI",
+            present: paths().any(is_ci_path),
+            points: 15,
+        },
+        HealthSignal {
+            id: "license",
+            name: "license",
+            // Only the repository's own license counts, not one from a vendored component
+            present: paths().any(|path| !path.contains('/') && is_license_file(path)),
+            points: 15,
+        },
+        HealthSignal {
+            id: "unknown-ratio",
+            name: "low Unknown ratio",
+            present: low_unknown,
+            points: 15,
+        },
+        HealthSignal {
+            id: "lockfile",
+            name: "lockfile",
+            present: has_lockfile,
+            points: 15,
+        },
+    ];
+
+    let score = signals.iter().filter(|s| s.present).map(|s| s.points).sum();
+    HealthReport { score, signals }
+}
+
+pub fn compute_health_score(analysis: &RepoAnalysis) -> u8 {
+    compute_health(analysis).score
+}
+
+pub fn display_health(report: &HealthReport) {
+    let breakdown: Vec<String> = report
+        .signals
+        .iter()
+        .map(|s| format!("{}{}", if s.present { "+" } else { "-" }, s.name))
+        .collect();
+    println!("Health: {}/100 ({})", report.score, breakdown.join(", "));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use crate::api::{analyze_repo, load_file_mappings};
+    use crate::detectors::DetectorRegistry;
+
+    async fn health_of(paths: &[&str]) -> HealthReport {
+        let files: HashMap<String, Vec<u8>> = paths
+            .iter()
+            .map(|path| (path.to_string(), b"# Title\n\nSome text.\n".to_vec()))
+            .collect();
+        let mappings = load_file_mappings().unwrap();
+        let analysis = analyze_repo(&[], &files, &mappings, &DetectorRegistry::with_defaults()).await;
+        compute_health(&analysis)
+    }
+
+    #[tokio::test]
+    async fn every_signal_present_scores_100() {
+        let report = health_of(&[
+            "README.md",
+            "LICENSE",
+            "Cargo.toml",
+            "Cargo.lock",
+            "src/main.rs",
+            "tests/cli.rs",
+            ".github/workflows/ci.yml",
+        ])
+        .await;
+        assert_eq!(report.score, 100);
+    }
+
+    #[tokio::test]
+    async fn missing_signals_cost_their_points() {
+        // No README, tests, CI, license or lockfile, and only Unknown files
+        let report = health_of(&["data/a.xyz", "data/b.xyz"]).await;
+        assert_eq!(report.score, 0);
+        assert!(report.signals.iter().all(|signal| !signal.present));
+    }
+
+    #[tokio::test]
+    async fn license_must_be_at_the_root() {
+        let signal = |report: HealthReport| report.signals.into_iter().find(|s| s.id == "license").unwrap();
+        assert!(signal(health_of(&["LICENSE-MIT"]).await).present);
+        assert!(signal(health_of(&["COPYING"]).await).present);
+        assert!(!signal(health_of(&["vendor/lib/LICENSE"]).await).present);
+    }
+}
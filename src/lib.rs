@@ -1,2 +1,30 @@
 pub mod api;
-pub mod display;
\ No newline at end of file
+pub mod archive;
+pub mod builder;
+pub mod cache;
+pub mod cli;
+pub mod client;
+pub mod config;
+pub mod deps;
+pub mod detectors;
+pub mod display;
+pub mod doctor;
+pub mod error;
+pub mod export;
+pub mod filter;
+pub mod forge;
+pub mod health;
+pub mod licenses;
+pub mod org;
+pub mod project_type;
+pub mod secrets;
+pub mod tui;
+pub mod watch;
+
+pub use api::{
+    analyze_repo, classify_path, load_file_mappings, DetectionConfidence, FileMappings, FileRecord, FileStats,
+    FileType, FileTypes, GitTree, RepoAnalysis, SkipRecord, Timings, TopLevelDir, TreeNode, TypeGroups, TypeMap,
+};
+pub use builder::AnalysisBuilder;
+pub use detectors::{Confidence, Detector, DetectorRegistry, Finding, FindingKind};
+pub use project_type::ProjectType;
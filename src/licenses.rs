@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+
+use crate::api::TreeNode;
+use crate::detectors::{Detector, Finding, FindingKind};
+
+/// Licenses that impose no obligations on code combined with them.
+const PERMISSIVE: &[&str] = &[
+    "MIT",
+    "Apache-2.0",
+    "BSD-2-Clause",
+    "BSD-3-Clause",
+    "ISC",
+    "Unlicense",
+];
+
+/// Strong copyleft: a component under one of these can relicense the whole work.
+const COPYLEFT: &[&str] = &["GPL-2.0", "GPL-3.0", "AGPL-3.0"];
+
+/// Whether `path` names a license file (`LICENSE`, `LICENCE.md`, `COPYING`, ...) in any directory.
+pub fn is_license_file(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path).to_ascii_uppercase();
+    name.starts_with("LICENSE") || name.starts_with("LICENCE") || name.starts_with("COPYING")
+}
+
+// Recognises the usual license texts by their opening lines; an SPDX tag wins
+pub fn identify_license(content: &str) -> Option<&'static str> {
+    let spdx = content
+        .lines()
+        .find_map(|line| line.split_once("SPDX-License-Identifier:"))
+        .map(|(_, id)| id.trim());
+    if let Some(id) = spdx {
+        let id = id.trim_end_matches("-only").trim_end_matches("-or-later");
+        return PERMISSIVE
+            .iter()
+            .chain(COPYLEFT)
+            .find(|known| **known == id)
+            .copied();
+    }
+
+    let upper = content.to_ascii_uppercase();
+    let version = |v: &str| upper.contains(&format!("VERSION {}", v));
+    if upper.contains("GNU AFFERO GENERAL PUBLIC LICENSE") {
+        Some("AGPL-3.0")
+    } else if upper.contains("GNU LESSER GENERAL PUBLIC LICENSE")
+        || upper.contains("GNU LIBRARY GENERAL PUBLIC LICENSE")
+    {
+        Some("LGPL")
+    } else if upper.contains("GNU GENERAL PUBLIC LICENSE") {
+        if version("3") {
+            Some("GPL-3.0")
+        } else {
+            Some("GPL-2.0")
+        }
+    } else if upper.contains("APACHE LICENSE") && version("2.0") {
+        Some("Apache-2.0")
+    } else if upper.contains("MOZILLA PUBLIC LICENSE") {
+        Some("MPL-2.0")
+    } else if upper.contains("PERMISSION IS HEREBY GRANTED, FREE OF CHARGE") {
+        Some("MIT")
+    } else if upper.contains("REDISTRIBUTION AND USE IN SOURCE AND BINARY FORMS") {
+        if upper.contains("NEITHER THE NAME") {
+            Some("BSD-3-Clause")
+        } else {
+            Some("BSD-2-Clause")
+        }
+    } else if upper.contains("PERMISSION TO USE, COPY, MODIFY, AND/OR DISTRIBUTE") {
+        Some("ISC")
+    } else if upper.contains("THIS IS FREE AND UNENCUMBERED SOFTWARE") {
+        Some("Unlicense")
+    } else {
+        None
+    }
+}
+
+// The root license file that names a recognised license, else the first root license file
+fn root_license<'a>(license_files: &[&'a String], files: &HashMap<String, Vec<u8>>) -> Option<(&'a String, Option<&'static str>)> {
+    let root_files = || license_files.iter().copied().filter(|path| !path.contains('/'));
+    root_files()
+        .find_map(|path| Some((path, Some(identify_license(&String::from_utf8_lossy(&files[path]))?))))
+        .or_else(|| root_files().next().map(|path| (path, None)))
+}
+
+fn sorted_license_files(files: &HashMap<String, Vec<u8>>) -> Vec<&String> {
+    let mut license_files: Vec<&String> = files.keys().filter(|path| is_license_file(path)).collect();
+    license_files.sort();
+    license_files
+}
+
+/// The repository's own license, from the license file at its root. Labelled with
+/// the SPDX identifier, or "unrecognized" when the text matches none we know.
+pub struct RootLicenseDetector;
+
+impl Detector for RootLicenseDetector {
+    fn name(&self) -> &'static str {
+        "license"
+    }
+
+    fn detect(&self, _tree: &[TreeNode], files: &HashMap<String, Vec<u8>>) -> Vec<Finding> {
+        let license_files = sorted_license_files(files);
+        let Some((path, license)) = root_license(&license_files, files) else {
+            return Vec::new();
+        };
+
+        vec![Finding::new(FindingKind::License, license.unwrap_or("unrecognized")).with_evidence(vec![path.clone()])]
+    }
+}
+
+/// Flags GPL/AGPL components vendored (with their own LICENSE file) into a
+/// repository whose root license is permissive. Dependency licenses from registries
+/// are out of reach, so only license files present in the tree are compared.
+pub struct LicenseConflictDetector;
+
+impl Detector for LicenseConflictDetector {
+    fn name(&self) -> &'static str {
+        "license-conflicts"
+    }
+
+    // Label is the component directory; details are [component license, root license]
+    fn detect(&self, _tree: &[TreeNode], files: &HashMap<String, Vec<u8>>) -> Vec<Finding> {
+        let license_of = |path: &String| identify_license(&String::from_utf8_lossy(&files[path]));
+
+        let license_files = sorted_license_files(files);
+        let root_license = root_license(&license_files, files)
+            .and_then(|(_, license)| license)
+            .filter(|license| PERMISSIVE.contains(license));
+        let Some(root_license) = root_license else {
+            return Vec::new();
+        };
+
+        license_files
+            .into_iter()
+            .filter_map(|path| {
+                let (dir, _) = path.rsplit_once('/')?;
+                let license = license_of(path).filter(|license| COPYLEFT.contains(license))?;
+                Some(
+                    Finding::new(FindingKind::LicenseConflict, dir)
+                        .with_details(vec![license.to_string(), root_license.to_string()])
+                        .with_evidence(vec![path.clone()]),
+                )
+            })
+            .collect()
+    }
+}
+
+pub fn display_license_conflicts(findings: &[&Finding]) {
+    for finding in findings {
+        println!(
+            "Potential license conflict: {} in {} vs {} root",
+            finding.details[0], finding.label, finding.details[1]
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIT: &str = "MIT License\n\nPermission is hereby granted, free of charge, to any person obtaining a copy\n";
+    const GPL3: &str = "GNU GENERAL PUBLIC LICENSE\nVersion 3, 29 June 2007\n";
+
+    #[test]
+    fn licenses_are_identified_by_spdx_tag_or_text() {
+        assert_eq!(identify_license("// SPDX-License-Identifier: GPL-2.0-or-later\n"), Some("GPL-2.0"));
+        assert_eq!(identify_license("SPDX-License-Identifier: WTFPL\n"), None);
+        assert_eq!(identify_license(MIT), Some("MIT"));
+        assert_eq!(identify_license(GPL3), Some("GPL-3.0"));
+        assert_eq!(identify_license("Apache License\nVersion 2.0, January 2004\n"), Some("Apache-2.0"));
+        assert_eq!(identify_license("All rights reserved.\n"), None);
+    }
+
+    #[test]
+    fn gpl_components_conflict_only_with_a_permissive_root() {
+        let files = |root: &str| -> HashMap<String, Vec<u8>> {
+            [("LICENSE", root), ("vendor/readline/COPYING", GPL3), ("vendor/zlib/LICENSE", MIT)]
+                .into_iter()
+                .map(|(path, content)| (path.to_string(), content.as_bytes().to_vec()))
+                .collect()
+        };
+
+        let findings = LicenseConflictDetector.detect(&[], &files(MIT));
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].label, "vendor/readline");
+        assert_eq!(findings[0].details, ["GPL-3.0", "MIT"]);
+        assert_eq!(findings[0].evidence, ["vendor/readline/COPYING"]);
+
+        assert!(LicenseConflictDetector.detect(&[], &files(GPL3)).is_empty());
+    }
+}
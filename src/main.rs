@@ -1,8 +1,81 @@
-use project_type_checker::api::fetch_and_display_tree; // Correct module path
+use clap::Parser;
+use project_type_checker::api::{fetch_and_display_tree, show_capabilities, show_rate_limit}; // Correct module path
+use project_type_checker::cli::{Cli, Command};
+use project_type_checker::config::expand_presets;
+use project_type_checker::doctor::run_doctor;
+use project_type_checker::org::show_org_report;
+use project_type_checker::watch::watch_repo;
+use tracing_subscriber::EnvFilter;
+
+// Logs go to stderr so the report on stdout stays clean for piping
+fn init_logging(log_level: Option<&str>) {
+    let filter = match log_level {
+        Some(level) => EnvFilter::new(level),
+        None => EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn")),
+    };
+
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .init();
+}
 
 #[tokio::main]
 async fn main() {
     use std::io::{self, Write};
+    let cli = match expand_presets(std::env::args_os().collect()) {
+        Ok(args) => Cli::parse_from(args),
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    };
+    init_logging(cli.options.log_level.as_deref());
+
+    if let Some(Command::Ratelimit) = &cli.command {
+        if let Err(err) = show_rate_limit(&cli.options).await {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(Command::Capabilities) = &cli.command {
+        if let Err(err) = show_capabilities() {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(Command::Doctor) = &cli.command {
+        if let Err(err) = run_doctor(&cli.options).await {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(org) = &cli.options.org {
+        if let Err(err) = show_org_report(org, &cli.options).await {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(url) = &cli.url {
+        let result = match cli.options.watch {
+            Some(interval) => watch_repo(url, &cli.options, interval).await,
+            None => fetch_and_display_tree(url, &cli.options).await,
+        };
+        if let Err(err) = result {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let mut input = String::new();
 
     loop {
@@ -16,8 +89,8 @@ async fn main() {
             break;
         }
 
-        if let Err(err) = fetch_and_display_tree(url).await {
+        if let Err(err) = fetch_and_display_tree(url, &cli.options).await {
             eprintln!("Error: {}", err);
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,197 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::error::Error;
+use tokio::task::{JoinSet, LocalSet};
+use tracing::{info, warn};
+
+use crate::api::parse_json;
+use crate::builder::AnalysisBuilder;
+use crate::cli::Options;
+use crate::client::{ClientConfig, GitHubClient};
+use crate::health::compute_health;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct OrgRepo {
+    pub name: String,
+    pub html_url: String,
+    pub language: Option<String>,
+    #[serde(default)]
+    pub archived: bool,
+}
+
+/// Outcome for one repository in an organization-wide run.
+#[derive(Debug, Clone)]
+pub struct RepoReport {
+    pub name: String,
+    pub language: Option<String>,
+    pub project_type: String,
+    /// Health signals that were absent (ids from `HealthSignal`)
+    pub missing: Vec<&'static str>,
+}
+
+const ORG_PAGE_SIZE: usize = 100;
+
+/// Lists every repository of `org`, following `page` until a short page.
+pub async fn fetch_org_repos(
+    client: &GitHubClient,
+    org: &str,
+) -> Result<Vec<OrgRepo>, Box<dyn Error>> {
+    let mut repos = Vec::new();
+
+    for page in 1.. {
+        let url = format!(
+            "https://api.github.com/orgs/{}/repos?per_page={}&page={}",
+            org, ORG_PAGE_SIZE, page
+        );
+        info!("Listing organization repos: {}", url);
+
+        let res = client.get_with_retry(&url).await?;
+        if !res.status().is_success() {
+            return Err(format!(
+                "Failed to list repositories of {}: {} - {}",
+                org,
+                res.status(),
+                res.text().await?
+            )
+            .into());
+        }
+
+        let batch: Vec<OrgRepo> = parse_json(res).await?;
+        let done = batch.len() < ORG_PAGE_SIZE;
+        repos.extend(batch);
+        if done {
+            break;
+        }
+    }
+
+    Ok(repos)
+}
+
+async fn analyze_org_repo(repo: OrgRepo, options: Options) -> Result<RepoReport, String> {
+    let analysis = AnalysisBuilder::with_options(&repo.html_url, options)
+        .run()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let missing = compute_health(&analysis)
+        .signals
+        .iter()
+        .filter(|s| !s.present)
+        .map(|s| s.id)
+        .collect();
+
+    Ok(RepoReport {
+        name: repo.name,
+        language: repo.language,
+        project_type: analysis.project_type.to_string(),
+        missing,
+    })
+}
+
+/// Analyzes every non-archived repository of `org`, up to `--concurrency` at a
+/// time, and prints an organization-wide summary.
+pub async fn show_org_report(org: &str, options: &Options) -> Result<(), Box<dyn Error>> {
+    let client = GitHubClient::new(&ClientConfig::from_options(options))?;
+    let repos: Vec<OrgRepo> = fetch_org_repos(&client, org)
+        .await?
+        .into_iter()
+        .filter(|repo| !repo.archived)
+        .collect();
+    let total = repos.len();
+
+    let mut pending = repos.into_iter();
+    let mut in_flight = JoinSet::new();
+    let mut reports = Vec::new();
+    let mut failed = Vec::new();
+
+    // Analysis errors are not `Send`, so the per-repo tasks stay on this thread
+    let local = LocalSet::new();
+    local
+        .run_until(async {
+            loop {
+                while in_flight.len() < options.concurrency.max(1) {
+                    let Some(repo) = pending.next() else {
+                        break;
+                    };
+                    let name = repo.name.clone();
+                    let options = options.clone();
+                    in_flight
+                        .spawn_local(async move { (name, analyze_org_repo(repo, options).await) });
+                }
+
+                let Some(joined) = in_flight.join_next().await else {
+                    break;
+                };
+                let (name, result) = joined?;
+                match result {
+                    Ok(report) => reports.push(report),
+                    Err(e) => {
+                        warn!("Failed to analyze {}: {}", name, e);
+                        failed.push(name.clone());
+                    }
+                }
+                eprintln!("[{}/{}] {}", reports.len() + failed.len(), total, name);
+            }
+            Ok::<_, Box<dyn Error>>(())
+        })
+        .await?;
+
+    reports.sort_by(|a, b| a.name.cmp(&b.name));
+    display_org_report(org, &reports, &failed);
+    Ok(())
+}
+
+fn print_counts(title: &str, counts: BTreeMap<&str, usize>) {
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    println!("{}:", title);
+    for (name, count) in counts {
+        println!("  {}: {}", name, count);
+    }
+}
+
+pub fn display_org_report(org: &str, reports: &[RepoReport], failed: &[String]) {
+    println!(
+        "Organization: {} ({} repositories analyzed, {} failed)",
+        org,
+        reports.len(),
+        failed.len()
+    );
+
+    let mut languages = BTreeMap::new();
+    let mut project_types = BTreeMap::new();
+    for report in reports {
+        *languages
+            .entry(report.language.as_deref().unwrap_or("None"))
+            .or_default() += 1;
+        *project_types
+            .entry(report.project_type.as_str())
+            .or_default() += 1;
+    }
+    print_counts("Languages", languages);
+    print_counts("Project types", project_types);
+
+    for (id, label) in [
+        ("readme", "README"),
+        ("tests", "tests"),
+        ("license", "license"),
+    ] {
+        let missing: Vec<&str> = reports
+            .iter()
+            .filter(|r| r.missing.contains(&id))
+            .map(|r| r.name.as_str())
+            .collect();
+        if !missing.is_empty() {
+            println!(
+                "Missing {} ({}): {}",
+                label,
+                missing.len(),
+                missing.join(", ")
+            );
+        }
+    }
+
+    if !failed.is_empty() {
+        println!("Failed: {}", failed.join(", "));
+    }
+}
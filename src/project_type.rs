@@ -0,0 +1,147 @@
+use serde::{Serialize, Serializer};
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+/// A recognised kind of project. `Display` gives the label shown in reports and
+/// `FromStr` reads it back; labels no variant covers become `Other`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ProjectType {
+    Website,
+    StaticWebsite,
+    /// A website whose framework is known, e.g. "Website using React"
+    WebsiteUsing(String),
+    /// A website plus the backend serving it, e.g. "Website with Go Backend"
+    WebsiteWith(Box<ProjectType>),
+    JavaBackend,
+    RubyBackend,
+    GoBackend,
+    PhpBackend,
+    KotlinBackend,
+    ScalaBackend,
+    RustBackend,
+    PythonBackend,
+    DotNetBackend,
+    NodeBackend,
+    MobileApp,
+    DesktopApp,
+    CliTool,
+    RustCliTool,
+    PythonCliTool,
+    CSharpCliTool,
+    JavaCliTool,
+    GradleCliTool,
+    GoCliTool,
+    RubyCliTool,
+    ShellAutomation,
+    SmartContract,
+    SolanaAnchor,
+    Move,
+    Unknown,
+    Other(String),
+}
+
+/// Labels of the variants that carry no data.
+const NAMED: &[(ProjectType, &str)] = &[
+    (ProjectType::Website, "Website"),
+    (ProjectType::StaticWebsite, "Static website"),
+    (ProjectType::JavaBackend, "Java Backend"),
+    (ProjectType::RubyBackend, "Ruby Backend (Rails)"),
+    (ProjectType::GoBackend, "Go Backend"),
+    (ProjectType::PhpBackend, "PHP Backend"),
+    (ProjectType::KotlinBackend, "Kotlin Backend"),
+    (ProjectType::ScalaBackend, "Scala Backend"),
+    (ProjectType::RustBackend, "Rust Backend"),
+    (ProjectType::PythonBackend, "Python Backend"),
+    (ProjectType::DotNetBackend, ".NET Backend"),
+    (ProjectType::NodeBackend, "Node.js Backend"),
+    (ProjectType::MobileApp, "Mobile App"),
+    (ProjectType::DesktopApp, "Desktop App"),
+    (ProjectType::CliTool, "CLI Tool"),
+    (ProjectType::RustCliTool, "Rust CLI Tool"),
+    (ProjectType::PythonCliTool, "Python CLI Tool"),
+    (ProjectType::CSharpCliTool, "C# CLI Tool"),
+    (ProjectType::JavaCliTool, "Java CLI Tool"),
+    (ProjectType::GradleCliTool, "Gradle (Java/Kotlin) CLI Tool"),
+    (ProjectType::GoCliTool, "Go CLI Tool"),
+    (ProjectType::RubyCliTool, "Ruby CLI Tool"),
+    (ProjectType::ShellAutomation, "Shell/Automation"),
+    (ProjectType::SmartContract, "Smart Contract (Solidity/Hardhat)"),
+    (ProjectType::SolanaAnchor, "Solana (Anchor)"),
+    (ProjectType::Move, "Move"),
+    (ProjectType::Unknown, "Unknown Project Type"),
+];
+
+impl ProjectType {
+    /// Backends that combine with a detected website into `WebsiteWith`.
+    pub const BACKENDS: &'static [ProjectType] = &[
+        ProjectType::RustBackend,
+        ProjectType::PythonBackend,
+        ProjectType::DotNetBackend,
+        ProjectType::NodeBackend,
+        ProjectType::JavaBackend,
+        ProjectType::RubyBackend,
+        ProjectType::GoBackend,
+        ProjectType::PhpBackend,
+        ProjectType::KotlinBackend,
+        ProjectType::ScalaBackend,
+    ];
+
+    /// Labels of every fixed project type, for introspection (`capabilities`).
+    pub fn known_labels() -> Vec<&'static str> {
+        NAMED
+            .iter()
+            .filter(|(project_type, _)| *project_type != ProjectType::Unknown)
+            .map(|(_, name)| *name)
+            .collect()
+    }
+
+    pub fn from_label(label: &str) -> Self {
+        if let Some(framework) = label.strip_prefix("Website using ") {
+            return ProjectType::WebsiteUsing(framework.to_string());
+        }
+        if let Some(backend) = label.strip_prefix("Website with ") {
+            return ProjectType::WebsiteWith(Box::new(Self::from_label(backend)));
+        }
+        if label == "C# Backend" {
+            return ProjectType::DotNetBackend;
+        }
+
+        NAMED
+            .iter()
+            .find(|(_, name)| *name == label)
+            .map_or_else(|| ProjectType::Other(label.to_string()), |(project_type, _)| project_type.clone())
+    }
+}
+
+impl fmt::Display for ProjectType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProjectType::WebsiteUsing(framework) => write!(f, "Website using {}", framework),
+            ProjectType::WebsiteWith(backend) => write!(f, "Website with {}", backend),
+            ProjectType::Other(label) => f.write_str(label),
+            named => {
+                let label = NAMED
+                    .iter()
+                    .find(|(project_type, _)| project_type == named)
+                    .map_or("Unknown Project Type", |(_, name)| name);
+                f.write_str(label)
+            }
+        }
+    }
+}
+
+impl FromStr for ProjectType {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from_label(s))
+    }
+}
+
+// Serialized as its label, so JSON output is unchanged
+impl Serialize for ProjectType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
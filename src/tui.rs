@@ -0,0 +1,236 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::api::{FileRecord, RepoAnalysis, TreeNode};
+use crate::display::type_color_index;
+
+// Same order as the text palette so a type looks alike in both views
+const TUI_PALETTE: &[Color] = &[
+    Color::Cyan,
+    Color::Magenta,
+    Color::Yellow,
+    Color::Green,
+    Color::Blue,
+    Color::Red,
+    Color::LightCyan,
+    Color::LightMagenta,
+];
+
+struct Entry {
+    path: String,
+    name: String,
+    depth: usize,
+    is_dir: bool,
+}
+
+struct App<'a> {
+    entries: Vec<Entry>,
+    expanded: HashSet<String>,
+    state: ListState,
+    analysis: &'a RepoAnalysis,
+    records: HashMap<&'a str, &'a FileRecord>,
+}
+
+/// Browses the fetched tree and analysis until the user quits with `q`/Esc.
+pub fn run(tree: &[TreeNode], analysis: &RepoAnalysis) -> io::Result<()> {
+    let mut terminal = ratatui::init();
+    let result = App::new(tree, analysis).run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+impl<'a> App<'a> {
+    fn new(tree: &[TreeNode], analysis: &'a RepoAnalysis) -> Self {
+        let mut entries: Vec<Entry> = tree
+            .iter()
+            .filter(|node| node.r#type == "tree" || node.r#type == "blob")
+            .map(|node| Entry {
+                path: node.path.clone(),
+                name: node.path.rsplit('/').next().unwrap_or(&node.path).to_string(),
+                depth: node.path.matches('/').count(),
+                is_dir: node.r#type == "tree",
+            })
+            .collect();
+        // Component-wise order keeps every directory directly above its contents
+        entries.sort_by(|a, b| a.path.split('/').cmp(b.path.split('/')));
+
+        let mut state = ListState::default();
+        if !entries.is_empty() {
+            state.select(Some(0));
+        }
+
+        Self {
+            entries,
+            expanded: HashSet::new(),
+            state,
+            analysis,
+            records: analysis.files.iter().map(|f| (f.path.as_str(), f)).collect(),
+        }
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+                KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+                KeyCode::Enter | KeyCode::Char(' ') => self.toggle(None),
+                KeyCode::Right | KeyCode::Char('l') => self.toggle(Some(true)),
+                KeyCode::Left | KeyCode::Char('h') => self.toggle(Some(false)),
+                _ => {}
+            }
+        }
+    }
+
+    fn is_visible(&self, entry: &Entry) -> bool {
+        let mut ancestor = entry.path.as_str();
+        while let Some((parent, _)) = ancestor.rsplit_once('/') {
+            if !self.expanded.contains(parent) {
+                return false;
+            }
+            ancestor = parent;
+        }
+        true
+    }
+
+    fn visible(&self) -> Vec<&Entry> {
+        self.entries.iter().filter(|e| self.is_visible(e)).collect()
+    }
+
+    fn selected(&self) -> Option<&Entry> {
+        let index = self.state.selected()?;
+        self.visible().into_iter().nth(index)
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let count = self.visible().len();
+        if count == 0 {
+            return;
+        }
+        let current = self.state.selected().unwrap_or(0) as isize;
+        let next = (current + delta).clamp(0, count as isize - 1);
+        self.state.select(Some(next as usize));
+    }
+
+    // `None` flips the selected directory; `Some(open)` forces it open or closed
+    fn toggle(&mut self, open: Option<bool>) {
+        let Some(entry) = self.selected().filter(|e| e.is_dir) else {
+            return;
+        };
+        let path = entry.path.clone();
+        let open = open.unwrap_or(!self.expanded.contains(&path));
+        if open {
+            self.expanded.insert(path);
+        } else {
+            self.expanded.remove(&path);
+        }
+    }
+
+    fn type_color(&self, path: &str) -> Color {
+        match self.records.get(path) {
+            Some(record) => TUI_PALETTE[type_color_index(&record.file_type) % TUI_PALETTE.len()],
+            None => Color::Reset,
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [tree_area, stats_area] =
+            Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .areas(frame.area());
+
+        let items: Vec<ListItem> = self
+            .visible()
+            .into_iter()
+            .map(|entry| {
+                let indent = "  ".repeat(entry.depth);
+                if entry.is_dir {
+                    let marker = if self.expanded.contains(&entry.path) { "▾" } else { "▸" };
+                    ListItem::new(Line::from(Span::styled(
+                        format!("{}{} {}/", indent, marker, entry.name),
+                        Style::default().fg(Color::Blue).add_modifier(Modifier::BOLD),
+                    )))
+                } else {
+                    ListItem::new(Line::from(Span::styled(
+                        format!("{}  {}", indent, entry.name),
+                        Style::default().fg(self.type_color(&entry.path)),
+                    )))
+                }
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title("Tree"))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        let stats = Paragraph::new(self.stats_lines())
+            .block(Block::default().borders(Borders::ALL).title("Stats"));
+
+        frame.render_stateful_widget(list, tree_area, &mut self.state);
+        frame.render_widget(stats, stats_area);
+    }
+
+    fn stats_lines(&self) -> Vec<Line<'static>> {
+        let mut lines = vec![
+            Line::from(format!(
+                "Project type: {} ({} confidence)",
+                self.analysis.project_type, self.analysis.confidence
+            )),
+            Line::from(format!("Files: {}", self.analysis.files.len())),
+            Line::from(""),
+        ];
+
+        match self.selected() {
+            Some(entry) if entry.is_dir => {
+                let prefix = format!("{}/", entry.path);
+                let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+                for record in self.analysis.files.iter().filter(|f| f.path.starts_with(&prefix)) {
+                    *counts.entry(record.file_type.as_str()).or_default() += 1;
+                }
+
+                lines.push(Line::from(format!("Directory: {}", prefix)));
+                lines.push(Line::from(format!("Files: {}", counts.values().sum::<usize>())));
+                let mut counts: Vec<_> = counts.into_iter().collect();
+                counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+                for (file_type, count) in counts {
+                    lines.push(Line::from(vec![
+                        Span::styled(
+                            format!("  {}", file_type),
+                            Style::default().fg(TUI_PALETTE[type_color_index(file_type) % TUI_PALETTE.len()]),
+                        ),
+                        Span::raw(format!(": {}", count)),
+                    ]));
+                }
+            }
+            Some(entry) => {
+                lines.push(Line::from(format!("File: {}", entry.path)));
+                if let Some(record) = self.records.get(entry.path.as_str()) {
+                    lines.push(Line::from(format!("Type: {} ({})", record.file_type, record.category)));
+                    lines.push(Line::from(format!("Size: {} bytes", record.size)));
+                    lines.push(Line::from(format!("Lines: {}", record.lines)));
+                } else {
+                    lines.push(Line::from("Not analyzed"));
+                }
+            }
+            None => {}
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from("↑/↓ move  ⏎ expand/collapse  q quit"));
+        lines
+    }
+}
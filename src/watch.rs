@@ -0,0 +1,180 @@
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::api::{
+    analyze_forge, extract_owner_repo, fetch_repo_info, parse_json, CommitRef, RepoAnalysis,
+};
+use crate::cli::Options;
+use crate::client::{ClientConfig, GitHubClient};
+use crate::forge::{GitHubForge, Host};
+
+/// Last seen head of the watched branch. Polling sends the ETag back, so an
+/// unchanged branch costs a 304, which GitHub does not count against the quota.
+#[derive(Default)]
+struct HeadCache {
+    etag: Option<String>,
+    sha: Option<String>,
+}
+
+impl HeadCache {
+    /// Returns the new head SHA, or `None` when the branch has not moved.
+    async fn check(
+        &mut self,
+        client: &GitHubClient,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+    ) -> Result<Option<String>, Box<dyn Error>> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/commits/{}",
+            owner, repo, branch
+        );
+        let mut request = client.get(&url);
+        if let Some(etag) = &self.etag {
+            request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+        }
+
+        let res = request.send().await?;
+        if res.status() == reqwest::StatusCode::NOT_MODIFIED {
+            return Ok(None);
+        }
+        if !res.status().is_success() {
+            return Err(format!(
+                "Failed to fetch head of {}: {} - {}",
+                branch,
+                res.status(),
+                res.text().await?
+            )
+            .into());
+        }
+
+        self.etag = res
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|etag| etag.to_str().ok())
+            .map(str::to_string);
+        let head: CommitRef = parse_json(res).await?;
+
+        if self.sha.as_deref() == Some(head.sha.as_str()) {
+            return Ok(None);
+        }
+        self.sha = Some(head.sha.clone());
+        Ok(Some(head.sha))
+    }
+}
+
+/// Re-analyzes a GitHub repository every `interval` seconds. Only runs where the
+/// branch head moved are analyzed; each prints how the composition changed.
+pub async fn watch_repo(url: &str, options: &Options, interval: u64) -> Result<(), Box<dyn Error>> {
+    let (host, owner, repo) = extract_owner_repo(url)?;
+    if host != Host::GitHub {
+        return Err("--watch only supports GitHub repositories".into());
+    }
+
+    let config = ClientConfig::from_options(options);
+    let branch = match &options.branch {
+        Some(branch) => branch.clone(),
+        None => {
+            fetch_repo_info(&GitHubClient::new(&config)?, &owner, &repo)
+                .await?
+                .default_branch
+        }
+    };
+    let options = Options {
+        branch: Some(branch.clone()),
+        ..options.clone()
+    };
+
+    println!("Watching {}/{}@{} every {}s", owner, repo, branch, interval);
+
+    let mut head = HeadCache::default();
+    let mut previous: Option<RepoAnalysis> = None;
+    let mut ticker = tokio::time::interval(Duration::from_secs(interval.max(1)));
+
+    loop {
+        ticker.tick().await;
+
+        // A fresh client per run so --deadline applies to each analysis, not the whole watch
+        let client = GitHubClient::new(&config)?;
+        let sha = match head.check(&client, &owner, &repo, &branch).await {
+            Ok(Some(sha)) => sha,
+            Ok(None) => {
+                info!("{}/{}@{} unchanged", owner, repo, branch);
+                continue;
+            }
+            Err(e) => {
+                warn!("{}", e);
+                continue;
+            }
+        };
+
+        let forge = GitHubForge::new(&client, &owner, &repo).with_concurrency(options.concurrency);
+        let analysis = match analyze_forge(&forge, &options).await {
+            Ok((_, analysis)) => analysis,
+            Err(e) => {
+                warn!("Analysis of {} failed: {}", short_sha(&sha), e);
+                continue;
+            }
+        };
+
+        match &previous {
+            Some(previous) => display_composition_diff(&sha, previous, &analysis),
+            None => display_composition(&sha, &analysis),
+        }
+        previous = Some(analysis);
+    }
+}
+
+fn short_sha(sha: &str) -> &str {
+    &sha[..sha.len().min(7)]
+}
+
+fn display_composition(sha: &str, analysis: &RepoAnalysis) {
+    println!(
+        "[{}] {} ({} confidence), {} files",
+        short_sha(sha),
+        analysis.project_type,
+        analysis.confidence,
+        analysis.files.len()
+    );
+    for (file_type, count) in analysis.type_counts() {
+        println!("  {}: {}", file_type, count);
+    }
+}
+
+fn display_composition_diff(sha: &str, before: &RepoAnalysis, after: &RepoAnalysis) {
+    let sha = short_sha(sha);
+    let mut changed = false;
+
+    if before.project_type != after.project_type {
+        println!(
+            "[{}] Project type: {} -> {}",
+            sha, before.project_type, after.project_type
+        );
+        changed = true;
+    }
+
+    let (old, new) = (before.type_counts(), after.type_counts());
+    let file_types: BTreeSet<&str> = old.keys().chain(new.keys()).copied().collect();
+    for file_type in file_types {
+        let was = old.get(file_type).copied().unwrap_or(0);
+        let is = new.get(file_type).copied().unwrap_or(0);
+        if was != is {
+            println!(
+                "[{}] {}: {} -> {} ({:+})",
+                sha,
+                file_type,
+                was,
+                is,
+                is as i64 - was as i64
+            );
+            changed = true;
+        }
+    }
+
+    if !changed {
+        println!("[{}] No composition changes", sha);
+    }
+}